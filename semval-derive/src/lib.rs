@@ -0,0 +1,544 @@
+#![deny(missing_docs)]
+#![deny(missing_debug_implementations)]
+#![deny(rustdoc::broken_intra_doc_links)]
+#![cfg_attr(test, deny(warnings))]
+
+//! # semval-derive
+//!
+//! Derive macros for [semval](https://docs.rs/semval).
+//!
+//! Please use these macros through the `derive` feature of `semval`
+//! instead of depending on this crate directly.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    ext::IdentExt as _, parse_macro_input, spanned::Spanned as _, Data, DeriveInput, Expr, Fields,
+    Ident, Type,
+};
+
+/// Derive `Validate` for a struct
+///
+/// The invalidity type is declared by the struct attribute
+/// `#[validate(invalidity = "...")]`. Only fields that are annotated
+/// with `#[validate(nested)]` or `#[validate(nested = "...")]` are
+/// validated recursively in order of their declaration, all other
+/// fields are ignored.
+///
+/// - `#[validate(nested)]` validates the field with `Context::validate_at`,
+///   i.e. the field's invalidity must be convertible into the invalidity
+///   of the struct.
+/// - `#[validate(nested = "map")]` validates the field with
+///   `Context::validate_at_with`, where `map` is typically the variant
+///   of the invalidity type that wraps the field's invalidity.
+///
+/// The names of the fields are recorded in the paths of the nested
+/// invalidities. Unnamed fields are recorded by their index like the
+/// elements of tuples, e.g. `[0]`.
+///
+/// Additional validation rules are added by a custom function that
+/// receives the context after all fields have been validated:
+///
+/// - `#[validate(custom = "path")]` with
+///   `fn(&Self, Context<Invalidity>) -> Context<Invalidity>`
+///
+/// Besides `validate` the macro also implements `validate_into`, i.e.
+/// the invalidities of nested fields are pushed directly into the given
+/// sink.
+///
+/// # Generated invalidity types
+///
+/// The struct attribute `#[validate(generate)]` defines the invalidity
+/// type with the name given by `invalidity`. The generated `enum` has the
+/// same visibility as the struct and contains one variant per nested field
+/// that wraps the field's invalidity. Variants are named after the fields
+/// in *UpperCamelCase* unless overridden by `#[validate(nested(variant = "..."))]`.
+/// A `From` implementation is generated for each nested field, which can be
+/// suppressed by `#[validate(nested(skip_from))]` if the invalidity types of
+/// multiple fields coincide.
+///
+/// The wrapped invalidity type is derived from the field type. The compiler
+/// is not able to prove that the generated `From` implementation is unique
+/// if the field type is a collection like `Vec<T>` and needs a hint by
+/// `#[validate(nested(invalidity = "..."))]` in this case.
+///
+/// - `#[validate(objectives(A, B, ...))]` adds unit variants for additional
+///   objectives that are checked by the custom validation function.
+/// - `#[validate(derive(...))]` adds derive macros for the generated type,
+///   which always derives `Debug`.
+///
+/// # Example
+///
+/// ```
+/// use semval::prelude::*;
+/// use semval_derive::Validate;
+///
+/// #[derive(Debug)]
+/// struct Quantity(usize);
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// enum QuantityInvalidity {
+///     MinValue,
+/// }
+///
+/// impl Validate for Quantity {
+///     type Invalidity = QuantityInvalidity;
+///
+///     fn validate(&self) -> ValidationResult<Self::Invalidity> {
+///         ValidationContext::new()
+///             .invalidate_if(self.0 < 1, QuantityInvalidity::MinValue)
+///             .into()
+///     }
+/// }
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// enum ReservationInvalidity {
+///     Quantity(QuantityInvalidity),
+///     Incomplete,
+/// }
+///
+/// #[derive(Validate)]
+/// #[validate(invalidity = "ReservationInvalidity", custom = "Reservation::validate_name")]
+/// struct Reservation {
+///     name: String,
+///     #[validate(nested = "ReservationInvalidity::Quantity")]
+///     quantity: Quantity,
+/// }
+///
+/// impl Reservation {
+///     fn validate_name(
+///         &self,
+///         context: ValidationContext<ReservationInvalidity>,
+///     ) -> ValidationContext<ReservationInvalidity> {
+///         context.invalidate_if(self.name.is_empty(), ReservationInvalidity::Incomplete)
+///     }
+/// }
+///
+/// #[derive(Validate)]
+/// #[validate(
+///     invalidity = "OrderInvalidity",
+///     generate,
+///     derive(Clone, Copy, PartialEq, Eq),
+///     objectives(Empty),
+///     custom = "Order::validate_reservations"
+/// )]
+/// struct Order {
+///     #[validate(nested(invalidity = "ReservationInvalidity"))]
+///     reservations: Vec<Reservation>,
+///     #[validate(nested(variant = "Bonus", skip_from))]
+///     bonus_quantity: Option<Quantity>,
+/// }
+///
+/// impl Order {
+///     fn validate_reservations(
+///         &self,
+///         context: ValidationContext<OrderInvalidity>,
+///     ) -> ValidationContext<OrderInvalidity> {
+///         context.invalidate_if(self.reservations.is_empty(), OrderInvalidity::Empty)
+///     }
+/// }
+///
+/// fn main() {
+///     let reservation = Reservation {
+///         name: String::new(),
+///         quantity: Quantity(0),
+///     };
+///     assert_eq!(2, reservation.validate().unwrap_err().into_iter().count());
+///
+///     let order = Order {
+///         reservations: vec![reservation],
+///         bonus_quantity: Some(Quantity(0)),
+///     };
+///     assert_eq!(
+///         vec![
+///             OrderInvalidity::Reservations(ReservationInvalidity::Quantity(
+///                 QuantityInvalidity::MinValue
+///             )),
+///             OrderInvalidity::Reservations(ReservationInvalidity::Incomplete),
+///             OrderInvalidity::Bonus(QuantityInvalidity::MinValue),
+///         ],
+///         order.validate().unwrap_err().into_iter().collect::<Vec<_>>(),
+///     );
+/// }
+/// ```
+#[proc_macro_derive(Validate, attributes(validate))]
+pub fn derive_validate(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_validate(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct StructAttrs {
+    invalidity: Option<Type>,
+    custom: Option<Expr>,
+    generate: bool,
+    objectives: Vec<Ident>,
+    derives: Vec<syn::Path>,
+}
+
+impl StructAttrs {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let mut attrs = Self::default();
+        for attr in input
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("validate"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("invalidity") {
+                    let value: syn::LitStr = meta.value()?.parse()?;
+                    attrs.invalidity = Some(value.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("custom") {
+                    let value: syn::LitStr = meta.value()?.parse()?;
+                    attrs.custom = Some(value.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("generate") {
+                    attrs.generate = true;
+                    Ok(())
+                } else if meta.path.is_ident("objectives") {
+                    meta.parse_nested_meta(|meta| {
+                        attrs.objectives.push(meta.path.require_ident()?.clone());
+                        Ok(())
+                    })
+                } else if meta.path.is_ident("derive") {
+                    meta.parse_nested_meta(|meta| {
+                        attrs.derives.push(meta.path);
+                        Ok(())
+                    })
+                } else {
+                    Err(meta.error("unsupported struct attribute"))
+                }
+            })?;
+        }
+        Ok(attrs)
+    }
+}
+
+/// How a field is validated
+enum Nested {
+    /// Merge the field's invalidities by converting them with `Into`
+    Into,
+    /// Merge the field's invalidities by mapping them explicitly
+    With(Expr),
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    nested: Option<Nested>,
+    variant: Option<Ident>,
+    invalidity: Option<Type>,
+    skip_from: bool,
+}
+
+impl FieldAttrs {
+    fn parse(field: &syn::Field) -> syn::Result<Self> {
+        let mut attrs = Self::default();
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("validate"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("nested") {
+                    attrs.nested = Some(Nested::Into);
+                    if meta.input.peek(syn::Token![=]) {
+                        let value: syn::LitStr = meta.value()?.parse()?;
+                        attrs.nested = Some(Nested::With(value.parse()?));
+                    } else if meta.input.peek(syn::token::Paren) {
+                        meta.parse_nested_meta(|meta| {
+                            if meta.path.is_ident("variant") {
+                                let value: syn::LitStr = meta.value()?.parse()?;
+                                attrs.variant = Some(value.parse()?);
+                                Ok(())
+                            } else if meta.path.is_ident("invalidity") {
+                                let value: syn::LitStr = meta.value()?.parse()?;
+                                attrs.invalidity = Some(value.parse()?);
+                                Ok(())
+                            } else if meta.path.is_ident("skip_from") {
+                                attrs.skip_from = true;
+                                Ok(())
+                            } else {
+                                Err(meta.error("unsupported nested field attribute"))
+                            }
+                        })?;
+                    }
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported field attribute"))
+                }
+            })?;
+        }
+        Ok(attrs)
+    }
+}
+
+fn expand_validate(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "Validate can only be derived for structs",
+            ))
+        }
+    };
+    let attrs = StructAttrs::parse(input)?;
+    let invalidity = attrs.invalidity.as_ref().ok_or_else(|| {
+        syn::Error::new(
+            input.span(),
+            "missing struct attribute #[validate(invalidity = \"...\")]",
+        )
+    })?;
+    let nested_fields = nested_fields(fields)?;
+    let (maps, generated) = if attrs.generate {
+        let generated = generate_invalidity(input, &attrs, &nested_fields)?;
+        let maps = nested_fields
+            .iter()
+            .map(|field| {
+                let variant = field.variant()?;
+                Ok(quote!(#invalidity::#variant))
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+        (maps, Some(generated))
+    } else {
+        if !attrs.objectives.is_empty() || !attrs.derives.is_empty() {
+            return Err(syn::Error::new(
+                input.span(),
+                "objectives and derives require #[validate(generate)]",
+            ));
+        }
+        if let Some(field) = nested_fields
+            .iter()
+            .find(|field| field.variant.is_some() || field.invalidity.is_some() || field.skip_from)
+        {
+            return Err(syn::Error::new(
+                field.member.span(),
+                "variant, invalidity, and skip_from require #[validate(generate)]",
+            ));
+        }
+        let maps = nested_fields
+            .iter()
+            .map(|field| match &field.nested {
+                Nested::Into => quote!(::core::convert::Into::into),
+                Nested::With(map) => quote!(#map),
+            })
+            .collect::<Vec<_>>();
+        (maps, None)
+    };
+    let nested_into = nested_fields
+        .iter()
+        .zip(&maps)
+        .map(|(field, map)| {
+            let segment = field.segment();
+            let member = &field.member;
+            let ty = &field.ty;
+            quote! {
+                ::semval::Validate::validate_into(
+                    &self.#member,
+                    &mut ::semval::sink::MapSink::new(
+                        &mut ::semval::sink::PrefixSink::new(
+                            sink,
+                            #segment,
+                        ),
+                        |invalidity: <#ty as ::semval::Validate>::Invalidity| (#map)(invalidity),
+                    ),
+                );
+            }
+        })
+        .collect::<Vec<_>>();
+    let custom_into = attrs.custom.as_ref().map(|custom| {
+        quote! {
+            if !::semval::sink::InvaliditySink::is_done(sink) {
+                #custom(self, ::semval::context::Context::new()).merge_into(sink);
+            }
+        }
+    });
+    // Without any nested fields or custom validation the sink is unused
+    let sink = if nested_into.is_empty() && custom_into.is_none() {
+        quote!(_sink)
+    } else {
+        quote!(sink)
+    };
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        #generated
+
+        impl #impl_generics ::semval::Validate for #ident #ty_generics #where_clause {
+            type Invalidity = #invalidity;
+
+            fn validate(&self) -> ::semval::Result<Self::Invalidity> {
+                ::semval::Validate::validate_within(self, ::semval::context::Context::new()).into()
+            }
+
+            fn validate_into(
+                &self,
+                #sink: &mut dyn ::semval::sink::InvaliditySink<Self::Invalidity>,
+            ) {
+                #( #nested_into )*
+                #custom_into
+            }
+        }
+    })
+}
+
+/// A field that is validated recursively
+struct NestedField {
+    member: syn::Member,
+    ty: Type,
+    nested: Nested,
+    variant: Option<Ident>,
+    invalidity: Option<Type>,
+    skip_from: bool,
+}
+
+impl NestedField {
+    /// The segment of the field in paths
+    fn segment(&self) -> TokenStream2 {
+        match &self.member {
+            syn::Member::Named(ident) => {
+                let name = ident.unraw().to_string();
+                quote!(::semval::path::PathSegment::Field(#name))
+            }
+            syn::Member::Unnamed(index) => {
+                let index = index.index as usize;
+                quote!(::semval::path::PathSegment::Index(#index))
+            }
+        }
+    }
+
+    /// The variant of a generated invalidity type
+    fn variant(&self) -> syn::Result<Ident> {
+        match (&self.variant, &self.member) {
+            (Some(variant), _) => Ok(variant.clone()),
+            (None, syn::Member::Named(ident)) => {
+                let name = ident.unraw().to_string();
+                Ok(Ident::new(&upper_camel_case(&name), ident.span()))
+            }
+            (None, syn::Member::Unnamed(index)) => Err(syn::Error::new(
+                index.span(),
+                "unnamed fields require #[validate(nested(variant = \"...\"))]",
+            )),
+        }
+    }
+}
+
+fn nested_fields(fields: &Fields) -> syn::Result<Vec<NestedField>> {
+    let mut nested_fields = Vec::with_capacity(fields.len());
+    for (index, field) in fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(index.into()),
+        };
+        let FieldAttrs {
+            nested,
+            variant,
+            invalidity,
+            skip_from,
+        } = FieldAttrs::parse(field)?;
+        if let Some(nested) = nested {
+            nested_fields.push(NestedField {
+                member,
+                ty: field.ty.clone(),
+                nested,
+                variant,
+                invalidity,
+                skip_from,
+            });
+        }
+    }
+    Ok(nested_fields)
+}
+
+fn generate_invalidity(
+    input: &DeriveInput,
+    attrs: &StructAttrs,
+    nested_fields: &[NestedField],
+) -> syn::Result<TokenStream2> {
+    let invalidity = match &attrs.invalidity {
+        Some(Type::Path(ty)) if ty.qself.is_none() && ty.path.get_ident().is_some() => {
+            ty.path.get_ident().unwrap()
+        }
+        invalidity => {
+            return Err(syn::Error::new(
+                invalidity.span(),
+                "the name of a generated invalidity type must be an identifier",
+            ))
+        }
+    };
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "invalidity types cannot be generated for generic structs",
+        ));
+    }
+    let mut variants = Vec::with_capacity(nested_fields.len() + attrs.objectives.len());
+    let mut from_impls = Vec::with_capacity(nested_fields.len());
+    for field in nested_fields {
+        if let Nested::With(map) = &field.nested {
+            return Err(syn::Error::new(
+                map.span(),
+                "explicit mappings are not supported for generated invalidity types",
+            ));
+        }
+        let variant = field.variant()?;
+        let ty = &field.ty;
+        let doc = format!("Invalidity of `{}`", quote!(#ty));
+        let wrapped = match &field.invalidity {
+            Some(wrapped) => quote!(#wrapped),
+            None => quote!(<#ty as ::semval::Validate>::Invalidity),
+        };
+        variants.push(quote! {
+            #[doc = #doc]
+            #variant(#wrapped),
+        });
+        if !field.skip_from {
+            from_impls.push(quote! {
+                impl ::core::convert::From<#wrapped> for #invalidity {
+                    fn from(from: #wrapped) -> Self {
+                        #invalidity::#variant(from)
+                    }
+                }
+            });
+        }
+    }
+    for objective in &attrs.objectives {
+        let doc = format!("Objective `{}`", objective);
+        variants.push(quote! {
+            #[doc = #doc]
+            #objective,
+        });
+    }
+    let vis = &input.vis;
+    let derives = &attrs.derives;
+    let doc = format!("Invalidities of `{}`", input.ident);
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Debug, #( #derives ),*)]
+        #vis enum #invalidity {
+            #( #variants )*
+        }
+
+        #( #from_impls )*
+    })
+}
+
+fn upper_camel_case(snake_case: &str) -> String {
+    snake_case
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
@@ -0,0 +1,329 @@
+use semval::prelude::*;
+use semval_derive::Validate;
+
+#[derive(Debug)]
+struct Quantity(usize);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum QuantityInvalidity {
+    MinValue,
+    LargeValue,
+}
+
+impl Validate for Quantity {
+    type Invalidity = QuantityInvalidity;
+
+    fn validate(&self) -> ValidationResult<Self::Invalidity> {
+        ValidationContext::new()
+            .invalidate_if(self.0 < 1, QuantityInvalidity::MinValue)
+            .warn_if(self.0 > 100, QuantityInvalidity::LargeValue)
+            .into()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum CustomerInvalidity {
+    NameEmpty,
+}
+
+#[derive(Debug, Validate)]
+#[validate(invalidity = "CustomerInvalidity", custom = "Customer::validate_name")]
+struct Customer {
+    name: String,
+}
+
+impl Customer {
+    fn validate_name(
+        &self,
+        context: ValidationContext<CustomerInvalidity>,
+    ) -> ValidationContext<CustomerInvalidity> {
+        context.invalidate_if(self.name.is_empty(), CustomerInvalidity::NameEmpty)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum ReservationInvalidity {
+    Customer(CustomerInvalidity),
+    Quantity(QuantityInvalidity),
+}
+
+impl From<QuantityInvalidity> for ReservationInvalidity {
+    fn from(from: QuantityInvalidity) -> Self {
+        ReservationInvalidity::Quantity(from)
+    }
+}
+
+#[derive(Debug, Validate)]
+#[validate(invalidity = "ReservationInvalidity")]
+struct Reservation {
+    #[validate(nested = "ReservationInvalidity::Customer")]
+    customer: Customer,
+    #[validate(nested)]
+    quantity: Quantity,
+    #[allow(dead_code)]
+    comment: Option<String>,
+}
+
+#[derive(Debug, Validate)]
+#[validate(invalidity = "QuantityInvalidity")]
+struct Quantities(
+    #[validate(nested)] Vec<Quantity>,
+    #[validate(nested)] Option<Quantity>,
+);
+
+#[derive(Debug, Validate)]
+#[validate(invalidity = "QuantityInvalidity")]
+struct Unchecked {
+    #[allow(dead_code)]
+    quantity: Quantity,
+}
+
+#[test]
+fn without_nested_fields() {
+    assert!(Unchecked {
+        quantity: Quantity(0)
+    }
+    .validate()
+    .is_ok());
+}
+
+#[test]
+fn valid_struct() {
+    let reservation = Reservation {
+        customer: Customer {
+            name: "Mr X".to_string(),
+        },
+        quantity: Quantity(1),
+        comment: None,
+    };
+    assert!(reservation.is_valid());
+}
+
+#[test]
+fn warnings_of_valid_fields() {
+    let reservation = Reservation {
+        customer: Customer {
+            name: "Mr X".to_string(),
+        },
+        quantity: Quantity(1000),
+        comment: None,
+    };
+    let warnings: Vec<_> = reservation
+        .validate()
+        .unwrap()
+        .into_iter()
+        .map(|(path, invalidity)| (path.to_string(), invalidity))
+        .collect();
+    assert_eq!(
+        vec![(
+            "quantity".to_string(),
+            ReservationInvalidity::Quantity(QuantityInvalidity::LargeValue)
+        )],
+        warnings
+    );
+}
+
+#[test]
+fn invalid_struct() {
+    let reservation = Reservation {
+        customer: Customer {
+            name: String::new(),
+        },
+        quantity: Quantity(0),
+        comment: None,
+    };
+    let invalidities: Vec<_> = reservation.validate().unwrap_err().into_iter().collect();
+    assert_eq!(
+        vec![
+            ReservationInvalidity::Customer(CustomerInvalidity::NameEmpty),
+            ReservationInvalidity::Quantity(QuantityInvalidity::MinValue),
+        ],
+        invalidities
+    );
+}
+
+#[test]
+fn fail_fast() {
+    let reservation = Reservation {
+        customer: Customer {
+            name: String::new(),
+        },
+        quantity: Quantity(0),
+        comment: None,
+    };
+    assert!(!reservation.is_valid());
+    let invalidities: Vec<_> = ValidationContext::fail_fast()
+        .validate(&reservation)
+        .into_iter()
+        .collect();
+    assert_eq!(
+        vec![ReservationInvalidity::Customer(
+            CustomerInvalidity::NameEmpty
+        )],
+        invalidities
+    );
+}
+
+#[test]
+fn tuple_struct() {
+    assert!(Quantities(vec![Quantity(1)], None).is_valid());
+    assert_eq!(
+        2,
+        Quantities(vec![Quantity(0), Quantity(1)], Some(Quantity(0)))
+            .validate()
+            .unwrap_err()
+            .into_iter()
+            .count()
+    );
+}
+
+#[derive(Debug, Validate)]
+#[validate(
+    invalidity = "OrderInvalidity",
+    generate,
+    derive(Clone, Copy, PartialEq, Eq),
+    objectives(Empty),
+    custom = "Order::validate_quantities"
+)]
+struct Order {
+    #[validate(nested)]
+    quantity: Quantity,
+    #[validate(nested(variant = "Extra", skip_from))]
+    extra_quantity: Option<Quantity>,
+    #[validate(nested(invalidity = "ReservationInvalidity"))]
+    reservations: Vec<Reservation>,
+}
+
+impl Order {
+    fn validate_quantities(
+        &self,
+        context: ValidationContext<OrderInvalidity>,
+    ) -> ValidationContext<OrderInvalidity> {
+        context.invalidate_if(
+            self.quantity.0 == 0 && self.extra_quantity.is_none(),
+            OrderInvalidity::Empty,
+        )
+    }
+}
+
+#[test]
+fn generated_invalidity() {
+    let order = Order {
+        quantity: Quantity(0),
+        extra_quantity: Some(Quantity(0)),
+        reservations: vec![],
+    };
+    let invalidities: Vec<_> = order.validate().unwrap_err().into_iter().collect();
+    assert_eq!(
+        vec![
+            OrderInvalidity::Quantity(QuantityInvalidity::MinValue),
+            OrderInvalidity::Extra(QuantityInvalidity::MinValue),
+        ],
+        invalidities
+    );
+    let order = Order {
+        quantity: Quantity(0),
+        extra_quantity: None,
+        reservations: vec![Reservation {
+            customer: Customer {
+                name: String::new(),
+            },
+            quantity: Quantity(1),
+            comment: None,
+        }],
+    };
+    let invalidities: Vec<_> = order.validate().unwrap_err().into_iter().collect();
+    assert_eq!(
+        vec![
+            OrderInvalidity::Quantity(QuantityInvalidity::MinValue),
+            OrderInvalidity::Reservations(ReservationInvalidity::Customer(
+                CustomerInvalidity::NameEmpty
+            )),
+            OrderInvalidity::Empty,
+        ],
+        invalidities
+    );
+}
+
+#[test]
+fn generated_from_invalidity() {
+    let invalidities: Vec<_> = ValidationContext::<OrderInvalidity>::new()
+        .validate(&Quantity(0))
+        .into_iter()
+        .collect();
+    assert_eq!(
+        vec![OrderInvalidity::Quantity(QuantityInvalidity::MinValue)],
+        invalidities
+    );
+}
+
+#[test]
+fn field_paths() {
+    let order = Order {
+        quantity: Quantity(0),
+        extra_quantity: None,
+        reservations: vec![
+            Reservation {
+                customer: Customer {
+                    name: "Mr X".to_string(),
+                },
+                quantity: Quantity(1),
+                comment: None,
+            },
+            Reservation {
+                customer: Customer {
+                    name: String::new(),
+                },
+                quantity: Quantity(1),
+                comment: None,
+            },
+        ],
+    };
+    let paths: Vec<_> = order
+        .validate()
+        .unwrap_err()
+        .into_iter_with_paths()
+        .map(|(path, _)| path.to_string())
+        .collect();
+    assert_eq!(vec!["quantity", "reservations[1].customer", ""], paths);
+    let paths: Vec<_> = Quantities(vec![Quantity(1), Quantity(0)], Some(Quantity(0)))
+        .validate()
+        .unwrap_err()
+        .into_iter_with_paths()
+        .map(|(path, _)| path.to_string())
+        .collect();
+    assert_eq!(vec!["[0][1]", "[1]"], paths);
+}
+
+#[test]
+fn validate_into() {
+    let order = Order {
+        quantity: Quantity(0),
+        extra_quantity: Some(Quantity(0)),
+        reservations: vec![Reservation {
+            customer: Customer {
+                name: String::new(),
+            },
+            quantity: Quantity(0),
+            comment: None,
+        }],
+    };
+    let paths = |context: ValidationContext<OrderInvalidity>| {
+        context
+            .into_iter_with_paths()
+            .map(|(path, invalidity)| (path.to_string(), invalidity))
+            .collect::<Vec<_>>()
+    };
+    let mut context = ValidationContext::new();
+    order.validate_into(&mut context);
+    assert_eq!(paths(order.validate().unwrap_err()), paths(context));
+    let mut context = ValidationContext::fail_fast();
+    order.validate_into(&mut context);
+    assert_eq!(
+        vec![(
+            "quantity".to_string(),
+            OrderInvalidity::Quantity(QuantityInvalidity::MinValue)
+        )],
+        paths(context)
+    );
+}
@@ -0,0 +1,497 @@
+use super::*;
+
+use crate::path::PathSegment;
+
+use core::{fmt, future::Future};
+use futures::future::{join_all, BoxFuture};
+
+/// Asynchronous validation
+///
+/// Needed for validations that depend on asynchronous operations,
+/// e.g. querying a repository if a referenced entity exists.
+///
+/// The returned future must be `Send` to enable the validation of
+/// nested values on multi-threaded executors.
+///
+/// # Example
+/// ```
+/// # use semval::prelude::*;
+/// # use semval::asynchronous::{AsyncContext, AsyncValidate};
+/// # use core::future::Future;
+/// struct Email(&'static str);
+///
+/// async fn is_registered(email: &str) -> bool {
+///     // ...query the repository...
+///     email == "registered@example.com"
+/// }
+///
+/// #[derive(Debug)]
+/// enum EmailInvalidity {
+///     Format,
+///     Registered,
+/// }
+///
+/// impl AsyncValidate for Email {
+///     type Invalidity = EmailInvalidity;
+///
+///     fn validate_async(
+///         &self,
+///     ) -> impl Future<Output = ValidationResult<Self::Invalidity>> + Send {
+///         async move {
+///             ValidationContext::new()
+///                 .invalidate_if(!self.0.contains('@'), EmailInvalidity::Format)
+///                 .invalidate_if(is_registered(self.0).await, EmailInvalidity::Registered)
+///                 .into()
+///         }
+///     }
+/// }
+///
+/// let emails = [Email("registered@example.com"), Email("new@example.com")];
+/// let context = futures_executor::block_on(
+///     AsyncContext::<EmailInvalidity>::new()
+///         .validate_async(&Email("new@example.com"))
+///         .validate_at_async("emails", &emails)
+///         .finish(),
+/// );
+/// let paths: Vec<_> = context
+///     .into_iter_with_paths()
+///     .map(|(path, _)| path.to_string())
+///     .collect();
+/// assert_eq!(vec!["emails[0]"], paths);
+/// ```
+pub trait AsyncValidate {
+    /// Invalidity objectives
+    type Invalidity: Invalidity;
+
+    /// Perform the validation asynchronously
+    fn validate_async(&self) -> impl Future<Output = Result<Self::Invalidity>> + Send;
+}
+
+/// `AsyncValidate` is implemented for any reference of a type
+/// that implements `AsyncValidate`.
+impl<V> AsyncValidate for &V
+where
+    V: AsyncValidate + ?Sized,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate_async(&self) -> impl Future<Output = Result<Self::Invalidity>> + Send {
+        (**self).validate_async()
+    }
+}
+
+/// Validate `Some` or otherwise implicitly evaluate to `Ok`
+/// in case of `None`
+impl<V> AsyncValidate for Option<V>
+where
+    V: AsyncValidate + Sync,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate_async(&self) -> impl Future<Output = Result<Self::Invalidity>> + Send {
+        let some = self.as_ref().map(AsyncValidate::validate_async);
+        async move {
+            if let Some(some) = some {
+                some.await
+            } else {
+                Ok(Default::default())
+            }
+        }
+    }
+}
+
+/// Validate all elements of a slice at their [indices](../path/enum.PathSegment.html#variant.Index) concurrently
+impl<V> AsyncValidate for [V]
+where
+    V: AsyncValidate + Sync,
+    V::Invalidity: Send,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate_async(&self) -> impl Future<Output = Result<Self::Invalidity>> + Send {
+        let elems = join_all(self.iter().map(AsyncValidate::validate_async));
+        async move {
+            elems
+                .await
+                .into_iter()
+                .enumerate()
+                .fold(Context::new(), |ctx, (index, res)| {
+                    ctx.merge_result_at_with(index.into(), res, core::convert::identity)
+                })
+                .into()
+        }
+    }
+}
+
+/// Validate all elements of an array concurrently
+impl<V, const N: usize> AsyncValidate for [V; N]
+where
+    V: AsyncValidate + Sync,
+    V::Invalidity: Send,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate_async(&self) -> impl Future<Output = Result<Self::Invalidity>> + Send {
+        self[..].validate_async()
+    }
+}
+
+impl<V> AsyncValidate for Vec<V>
+where
+    V: AsyncValidate + Sync,
+    V::Invalidity: Send,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate_async(&self) -> impl Future<Output = Result<Self::Invalidity>> + Send {
+        self.as_slice().validate_async()
+    }
+}
+
+/// A validation context for asynchronous validations
+///
+/// Synchronous validations are performed immediately like
+/// within a [`Context`](../context/struct.Context.html). Asynchronous
+/// validations are deferred and performed concurrently when
+/// [finishing](#method.finish) the context.
+///
+/// The invalidities of all asynchronous validations are merged in
+/// the order in which the validations have been added, after the
+/// invalidities of all synchronous validations.
+pub struct AsyncContext<'a, V>
+where
+    V: Invalidity,
+{
+    context: Context<V>,
+    pending: Vec<BoxFuture<'a, Context<V>>>,
+}
+
+impl<V> fmt::Debug for AsyncContext<'_, V>
+where
+    V: Invalidity,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncContext")
+            .field("context", &self.context)
+            .field("pending", &self.pending.len())
+            .finish()
+    }
+}
+
+impl<V> Default for AsyncContext<'_, V>
+where
+    V: Invalidity,
+{
+    fn default() -> Self {
+        Context::new().into()
+    }
+}
+
+impl<V> From<Context<V>> for AsyncContext<'_, V>
+where
+    V: Invalidity,
+{
+    fn from(context: Context<V>) -> Self {
+        Self {
+            context,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl<'a, V> AsyncContext<'a, V>
+where
+    V: Invalidity + Send,
+{
+    /// Create a new valid and empty context
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Record a new invalidity within this context
+    pub fn invalidate(self, invalidity: impl Into<V>) -> Self {
+        self.with_context(|context| context.invalidate(invalidity))
+    }
+
+    /// Conditionally record a new invalidity within this context
+    pub fn invalidate_if(self, is_invalid: impl Into<bool>, invalidity: impl Into<V>) -> Self {
+        self.with_context(|context| context.invalidate_if(is_invalid, invalidity))
+    }
+
+    /// Validate the target synchronously and merge the result into this context
+    pub fn validate<U>(self, target: &impl Validate<Invalidity = U>) -> Self
+    where
+        U: Invalidity + Into<V>,
+    {
+        self.with_context(|context| context.validate(target))
+    }
+
+    /// Validate the target synchronously and merge the mapped result into this context
+    pub fn validate_with<F, U>(self, target: &impl Validate<Invalidity = U>, map: F) -> Self
+    where
+        F: Fn(U) -> V,
+        U: Invalidity,
+    {
+        self.with_context(|context| context.validate_with(target, map))
+    }
+
+    /// Validate the target synchronously and merge the result into this context
+    /// at the given path segment
+    pub fn validate_at<U>(
+        self,
+        segment: impl Into<PathSegment>,
+        target: &impl Validate<Invalidity = U>,
+    ) -> Self
+    where
+        U: Invalidity + Into<V>,
+    {
+        self.with_context(|context| context.validate_at(segment, target))
+    }
+
+    /// Validate the target synchronously and merge the mapped result into this context
+    /// at the given path segment
+    pub fn validate_at_with<F, U>(
+        self,
+        segment: impl Into<PathSegment>,
+        target: &impl Validate<Invalidity = U>,
+        map: F,
+    ) -> Self
+    where
+        F: Fn(U) -> V,
+        U: Invalidity,
+    {
+        self.with_context(|context| context.validate_at_with(segment, target, map))
+    }
+
+    /// Validate the target asynchronously and merge the result into this context
+    pub fn validate_async<U>(self, target: &'a impl AsyncValidate<Invalidity = U>) -> Self
+    where
+        U: Invalidity + Into<V> + Send,
+    {
+        self.validate_async_with(target, Into::into)
+    }
+
+    /// Validate the target asynchronously and merge the mapped result into this context
+    pub fn validate_async_with<F, U>(
+        mut self,
+        target: &'a impl AsyncValidate<Invalidity = U>,
+        map: F,
+    ) -> Self
+    where
+        F: Fn(U) -> V + Send + 'a,
+        U: Invalidity + Send,
+    {
+        let res = target.validate_async();
+        self.pending.push(Box::pin(async move {
+            Context::new().merge_result_with(res.await, map)
+        }));
+        self
+    }
+
+    /// Validate the target asynchronously and merge the result into this context
+    /// at the given path segment
+    pub fn validate_at_async<U>(
+        self,
+        segment: impl Into<PathSegment>,
+        target: &'a impl AsyncValidate<Invalidity = U>,
+    ) -> Self
+    where
+        U: Invalidity + Into<V> + Send,
+    {
+        self.validate_at_async_with(segment, target, Into::into)
+    }
+
+    /// Validate the target asynchronously and merge the mapped result into this context
+    /// at the given path segment
+    pub fn validate_at_async_with<F, U>(
+        mut self,
+        segment: impl Into<PathSegment>,
+        target: &'a impl AsyncValidate<Invalidity = U>,
+        map: F,
+    ) -> Self
+    where
+        F: Fn(U) -> V + Send + 'a,
+        U: Invalidity + Send,
+    {
+        let segment = segment.into();
+        let res = target.validate_async();
+        self.pending.push(Box::pin(async move {
+            Context::new().merge_result_at_with(segment, res.await, map)
+        }));
+        self
+    }
+
+    /// Perform all pending asynchronous validations concurrently
+    /// and merge their results
+    ///
+    /// Pending validations are skipped if the context is in
+    /// [fail-fast](../context/struct.Context.html#method.fail_fast)
+    /// mode and already invalid.
+    pub async fn finish(self) -> Context<V> {
+        let Self { context, pending } = self;
+        if context.is_fail_fast() && !context.is_valid() {
+            return context;
+        }
+        join_all(pending)
+            .await
+            .into_iter()
+            .fold(context, Mergeable::merge)
+    }
+
+    /// Perform all pending asynchronous validations concurrently
+    /// and finish the validation with a result
+    pub async fn into_result(self) -> Result<V> {
+        self.finish().await.into_result()
+    }
+
+    fn with_context(mut self, f: impl FnOnce(Context<V>) -> Context<V>) -> Self {
+        self.context = f(self.context);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::channel::oneshot;
+    use futures_executor::block_on;
+    use std::sync::Mutex;
+
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    enum Invalid {
+        Async,
+        Sync,
+    }
+
+    struct AsyncLeaf(bool);
+
+    impl AsyncValidate for AsyncLeaf {
+        type Invalidity = Invalid;
+
+        fn validate_async(&self) -> impl Future<Output = Result<Self::Invalidity>> + Send {
+            let is_valid = self.0;
+            async move {
+                futures::future::ready(()).await;
+                Context::new()
+                    .invalidate_if(!is_valid, Invalid::Async)
+                    .into()
+            }
+        }
+    }
+
+    struct SyncLeaf(bool);
+
+    impl Validate for SyncLeaf {
+        type Invalidity = Invalid;
+
+        fn validate(&self) -> Result<Self::Invalidity> {
+            Context::new().invalidate_if(!self.0, Invalid::Sync).into()
+        }
+    }
+
+    fn paths(context: Context<Invalid>) -> Vec<(String, Invalid)> {
+        context
+            .into_iter_with_paths()
+            .map(|(path, invalidity)| (path.to_string(), invalidity))
+            .collect()
+    }
+
+    #[test]
+    fn validate_option_and_slices() {
+        assert!(block_on(None::<AsyncLeaf>.validate_async()).is_ok());
+        assert!(block_on(Some(AsyncLeaf(false)).validate_async()).is_err());
+        let leafs = vec![AsyncLeaf(true), AsyncLeaf(false), AsyncLeaf(false)];
+        let context = block_on(leafs.validate_async()).unwrap_err();
+        assert_eq!(
+            vec![
+                ("[1]".to_string(), Invalid::Async),
+                ("[2]".to_string(), Invalid::Async)
+            ],
+            paths(context)
+        );
+    }
+
+    #[test]
+    fn mixed_context() {
+        let leafs = [AsyncLeaf(false), AsyncLeaf(true)];
+        let context = block_on(
+            AsyncContext::new()
+                .validate_at_async("async", &AsyncLeaf(false))
+                .validate_at("sync", &SyncLeaf(false))
+                .validate_at_async("leafs", &leafs)
+                .validate(&SyncLeaf(true))
+                .finish(),
+        );
+        assert_eq!(
+            vec![
+                ("sync".to_string(), Invalid::Sync),
+                ("async".to_string(), Invalid::Async),
+                ("leafs[0]".to_string(), Invalid::Async),
+            ],
+            paths(context)
+        );
+        assert!(block_on(
+            AsyncContext::<Invalid>::new()
+                .validate_async(&AsyncLeaf(true))
+                .into_result()
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn fail_fast_skips_pending_validations() {
+        let context = block_on(
+            AsyncContext::from(Context::fail_fast())
+                .invalidate(Invalid::Sync)
+                .validate_async(&AsyncLeaf(false))
+                .finish(),
+        );
+        assert_eq!(vec![(String::new(), Invalid::Sync)], paths(context));
+    }
+
+    /// Resolves only after the counterpart has been validated
+    struct Handshake {
+        receiver: Mutex<Option<oneshot::Receiver<()>>>,
+        sender: Mutex<Option<oneshot::Sender<()>>>,
+    }
+
+    impl AsyncValidate for Handshake {
+        type Invalidity = Invalid;
+
+        fn validate_async(&self) -> impl Future<Output = Result<Self::Invalidity>> + Send {
+            let receiver = self.receiver.lock().unwrap().take();
+            let sender = self.sender.lock().unwrap().take();
+            async move {
+                if let Some(sender) = sender {
+                    sender.send(()).unwrap();
+                }
+                if let Some(receiver) = receiver {
+                    receiver.await.unwrap();
+                }
+                Ok(Default::default())
+            }
+        }
+    }
+
+    #[test]
+    fn concurrent_validations() {
+        let (sender, receiver) = oneshot::channel();
+        let waiting = Handshake {
+            receiver: Mutex::new(Some(receiver)),
+            sender: Mutex::new(None),
+        };
+        let signaling = Handshake {
+            receiver: Mutex::new(None),
+            sender: Mutex::new(Some(sender)),
+        };
+        // Would never finish if validated sequentially
+        assert!(block_on(
+            AsyncContext::<Invalid>::new()
+                .validate_async(&waiting)
+                .validate_async(&signaling)
+                .into_result()
+        )
+        .is_ok());
+    }
+}
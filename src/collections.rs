@@ -0,0 +1,512 @@
+use crate::Validate;
+
+#[cfg(feature = "alloc")]
+use crate::{
+    context::Context,
+    sink::{InvaliditySink, MapSink, PrefixSink},
+    Invalidity, Result,
+};
+
+#[cfg(feature = "alloc")]
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque};
+
+#[cfg(feature = "alloc")]
+use core::fmt::Debug;
+
+#[cfg(feature = "std")]
+use std::{
+    collections::{HashMap, HashSet},
+    hash::BuildHasher,
+};
+
+/// An invalidity of an element at a certain position
+///
+/// Wraps the invalidities of elements that have been validated
+/// by [`Context::validate_each_with`](../context/struct.Context.html#method.validate_each_with)
+/// to identify which element failed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct Indexed<V> {
+    /// The position of the element
+    pub index: usize,
+
+    /// The invalidity of the element
+    pub invalidity: V,
+}
+
+impl<V> Indexed<V> {
+    /// Create a new indexed invalidity
+    pub const fn new(index: usize, invalidity: V) -> Self {
+        Self { index, invalidity }
+    }
+
+    /// Map the wrapped invalidity
+    pub fn map<F, U>(self, map: F) -> Indexed<U>
+    where
+        F: FnOnce(V) -> U,
+    {
+        let Self { index, invalidity } = self;
+        Indexed {
+            index,
+            invalidity: map(invalidity),
+        }
+    }
+}
+
+/// An invalidity of a map entry with a certain key
+///
+/// Wraps the invalidities of values in maps to identify which
+/// entry failed. The key is cloned from the map.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct Keyed<K, V> {
+    /// The key of the entry
+    pub key: K,
+
+    /// The invalidity of the entry
+    pub invalidity: V,
+}
+
+impl<K, V> Keyed<K, V> {
+    /// Create a new keyed invalidity
+    pub const fn new(key: K, invalidity: V) -> Self {
+        Self { key, invalidity }
+    }
+
+    /// Map the wrapped invalidity
+    pub fn map<F, U>(self, map: F) -> Keyed<K, U>
+    where
+        F: FnOnce(V) -> U,
+    {
+        let Self { key, invalidity } = self;
+        Keyed {
+            key,
+            invalidity: map(invalidity),
+        }
+    }
+}
+
+/// Invalidities of either the key or the value of a map entry
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub enum EntryInvalidity<K, V> {
+    /// Invalid key
+    Key(K),
+
+    /// Invalid value
+    Value(V),
+}
+
+/// Invalidities of map entries with both keys and values being validated
+pub type KeyedEntryInvalidity<K, V> =
+    Keyed<K, EntryInvalidity<<K as Validate>::Invalidity, <V as Validate>::Invalidity>>;
+
+/// Validate both keys and values of a map
+///
+/// Maps implement `Validate` by only validating their values. Wrap
+/// a borrowed map into this type to validate the keys as well.
+#[derive(Clone, Copy, Debug)]
+pub struct KeysAndValues<'a, M>(pub &'a M);
+
+/// Validate all elements of an iterator in order, unaware of their position
+#[cfg(feature = "alloc")]
+fn validate_elements<'a, V>(
+    elements: impl Iterator<Item = &'a V>,
+    sink: &mut dyn InvaliditySink<V::Invalidity>,
+) where
+    V: Validate + 'a,
+{
+    for elem in elements {
+        if sink.is_done() {
+            break;
+        }
+        elem.validate_into(sink);
+    }
+}
+
+/// Validate all elements of an iterator and record their position in the paths
+#[cfg(feature = "alloc")]
+fn validate_sequence<'a, V>(
+    elements: impl Iterator<Item = &'a V>,
+    sink: &mut dyn InvaliditySink<V::Invalidity>,
+) where
+    V: Validate + 'a,
+{
+    for (index, elem) in elements.enumerate() {
+        if sink.is_done() {
+            break;
+        }
+        elem.validate_into(&mut PrefixSink::new(sink, index.into()));
+    }
+}
+
+/// Validate all values of a map and wrap their invalidities together with the keys
+#[cfg(feature = "alloc")]
+fn validate_values<'a, K, V>(
+    entries: impl Iterator<Item = (&'a K, &'a V)>,
+    sink: &mut dyn InvaliditySink<Keyed<K, V::Invalidity>>,
+) where
+    K: Clone + Invalidity,
+    V: Validate + 'a,
+{
+    for (key, value) in entries {
+        if sink.is_done() {
+            break;
+        }
+        value.validate_into(&mut MapSink::new(sink, |invalidity: V::Invalidity| {
+            Keyed::new(key.clone(), invalidity)
+        }));
+    }
+}
+
+/// Validate all keys and values of a map and wrap their invalidities together with the keys
+#[cfg(feature = "alloc")]
+fn validate_keys_and_values<'a, K, V>(
+    entries: impl Iterator<Item = (&'a K, &'a V)>,
+    sink: &mut dyn InvaliditySink<KeyedEntryInvalidity<K, V>>,
+) where
+    K: Validate + Clone + Invalidity,
+    V: Validate + 'a,
+{
+    for (key, value) in entries {
+        if sink.is_done() {
+            break;
+        }
+        key.validate_into(&mut MapSink::new(sink, |invalidity: K::Invalidity| {
+            Keyed::new(key.clone(), EntryInvalidity::Key(invalidity))
+        }));
+        if sink.is_done() {
+            break;
+        }
+        value.validate_into(&mut MapSink::new(sink, |invalidity: V::Invalidity| {
+            Keyed::new(key.clone(), EntryInvalidity::Value(invalidity))
+        }));
+    }
+}
+
+/// Validate all values of a map
+#[cfg(feature = "std")]
+impl<K, V, S> Validate for HashMap<K, V, S>
+where
+    K: Clone + Debug + 'static,
+    V: Validate,
+    S: BuildHasher,
+{
+    type Invalidity = Keyed<K, V::Invalidity>;
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        self.validate_within(Context::new()).into()
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        validate_values(self.iter(), sink);
+    }
+}
+
+/// Validate all keys and values of a map
+#[cfg(feature = "std")]
+impl<K, V, S> Validate for KeysAndValues<'_, HashMap<K, V, S>>
+where
+    K: Validate + Clone + Debug + 'static,
+    V: Validate,
+    S: BuildHasher,
+{
+    type Invalidity = KeyedEntryInvalidity<K, V>;
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        self.validate_within(Context::new()).into()
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        validate_keys_and_values(self.0.iter(), sink);
+    }
+}
+
+/// Validate all values of a map in order of their keys
+#[cfg(feature = "alloc")]
+impl<K, V> Validate for BTreeMap<K, V>
+where
+    K: Clone + Debug + 'static,
+    V: Validate,
+{
+    type Invalidity = Keyed<K, V::Invalidity>;
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        self.validate_within(Context::new()).into()
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        validate_values(self.iter(), sink);
+    }
+}
+
+/// Validate all keys and values of a map in order of their keys
+#[cfg(feature = "alloc")]
+impl<K, V> Validate for KeysAndValues<'_, BTreeMap<K, V>>
+where
+    K: Validate + Clone + Debug + 'static,
+    V: Validate,
+{
+    type Invalidity = KeyedEntryInvalidity<K, V>;
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        self.validate_within(Context::new()).into()
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        validate_keys_and_values(self.0.iter(), sink);
+    }
+}
+
+/// Validate all elements of a set
+#[cfg(feature = "std")]
+impl<V, S> Validate for HashSet<V, S>
+where
+    V: Validate,
+    S: BuildHasher,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        self.validate_within(Context::new()).into()
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        validate_elements(self.iter(), sink);
+    }
+}
+
+/// Validate all elements of a set in order
+#[cfg(feature = "alloc")]
+impl<V> Validate for BTreeSet<V>
+where
+    V: Validate,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        self.validate_within(Context::new()).into()
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        validate_elements(self.iter(), sink);
+    }
+}
+
+/// Validate all elements of a heap in arbitrary order
+#[cfg(feature = "alloc")]
+impl<V> Validate for BinaryHeap<V>
+where
+    V: Validate,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        self.validate_within(Context::new()).into()
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        validate_elements(self.iter(), sink);
+    }
+}
+
+/// Validate all elements of a queue at their [indices](path/enum.PathSegment.html#variant.Index)
+#[cfg(feature = "alloc")]
+impl<V> Validate for VecDeque<V>
+where
+    V: Validate,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        self.validate_within(Context::new()).into()
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        validate_sequence(self.iter(), sink);
+    }
+}
+
+/// Validate all elements of a list at their [indices](path/enum.PathSegment.html#variant.Index)
+#[cfg(feature = "alloc")]
+impl<V> Validate for LinkedList<V>
+where
+    V: Validate,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        self.validate_within(Context::new()).into()
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        validate_sequence(self.iter(), sink);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{context::Context, test_fixtures::Leaf};
+
+    #[derive(Debug, Eq, PartialEq)]
+    enum BatchInvalidity {
+        Record(Indexed<()>),
+    }
+
+    impl From<Indexed<()>> for BatchInvalidity {
+        fn from(from: Indexed<()>) -> Self {
+            BatchInvalidity::Record(from)
+        }
+    }
+
+    #[test]
+    fn validate_each_with() {
+        let records = [Leaf(false), Leaf(true), Leaf(false)];
+        let invalidities: Vec<_> = Context::new()
+            .validate_each_with(&records, BatchInvalidity::Record)
+            .into_iter()
+            .collect();
+        assert_eq!(
+            vec![
+                BatchInvalidity::Record(Indexed::new(0, ())),
+                BatchInvalidity::Record(Indexed::new(2, ())),
+            ],
+            invalidities
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn validate_each() {
+        let records = [Leaf(true), Leaf(false)];
+        let context = Context::<BatchInvalidity>::new().validate_each(records.iter());
+        let invalidities: Vec<_> = context.into_iter_with_paths().collect();
+        assert_eq!(
+            vec![(
+                crate::path::Path::from(crate::path::PathSegment::Index(1)),
+                BatchInvalidity::Record(Indexed::new(1, ()))
+            )],
+            invalidities
+        );
+    }
+
+    #[test]
+    fn map_indexed() {
+        assert_eq!(Indexed::new(3, 2), Indexed::new(3, 1).map(|x| x + 1));
+    }
+
+    #[test]
+    fn map_keyed() {
+        assert_eq!(Keyed::new("a", 2), Keyed::new("a", 1).map(|x| x + 1));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn validate_maps() {
+        let mut map = BTreeMap::new();
+        map.insert(1, Leaf(true));
+        assert!(map.validate().is_ok());
+        map.insert(2, Leaf(false));
+        map.insert(3, Leaf(false));
+        let invalidities: Vec<_> = map.validate().unwrap_err().into_iter().collect();
+        assert_eq!(vec![Keyed::new(2, ()), Keyed::new(3, ())], invalidities);
+        let map: HashMap<_, _> = map.into_iter().collect();
+        let mut invalidities: Vec<_> = map.validate().unwrap_err().into_iter().collect();
+        invalidities.sort();
+        assert_eq!(vec![Keyed::new(2, ()), Keyed::new(3, ())], invalidities);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn validate_map_keys_and_values() {
+        let mut map = BTreeMap::new();
+        map.insert(Leaf(true), Leaf(false));
+        map.insert(Leaf(false), Leaf(true));
+        assert_eq!(1, map.validate().unwrap_err().into_iter().count());
+        let invalidities: Vec<_> = KeysAndValues(&map)
+            .validate()
+            .unwrap_err()
+            .into_iter()
+            .collect();
+        assert_eq!(
+            vec![
+                Keyed::new(Leaf(false), EntryInvalidity::Key(())),
+                Keyed::new(Leaf(true), EntryInvalidity::Value(())),
+            ],
+            invalidities
+        );
+        let map: HashMap<_, _> = map.into_iter().collect();
+        assert_eq!(
+            2,
+            KeysAndValues(&map)
+                .validate()
+                .unwrap_err()
+                .into_iter()
+                .count()
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn validate_sets() {
+        let set: BTreeSet<_> = vec![Leaf(true), Leaf(false)].into_iter().collect();
+        assert_eq!(1, set.validate().unwrap_err().into_iter().count());
+        let set: HashSet<_> = set.into_iter().collect();
+        assert_eq!(1, set.validate().unwrap_err().into_iter().count());
+        let heap: BinaryHeap<_> = set.into_iter().collect();
+        assert_eq!(1, heap.validate().unwrap_err().into_iter().count());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn validate_sequences() {
+        let queue: VecDeque<_> = vec![Leaf(true), Leaf(false)].into_iter().collect();
+        let paths: Vec<_> = queue
+            .validate()
+            .unwrap_err()
+            .into_iter_with_paths()
+            .map(|(path, ())| path)
+            .collect();
+        assert_eq!(
+            vec![crate::path::Path::from(crate::path::PathSegment::Index(1))],
+            paths
+        );
+        let list: LinkedList<_> = queue.into_iter().collect();
+        assert_eq!(1, list.validate().unwrap_err().into_iter().count());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn validate_sequence_fail_fast() {
+        use crate::test_fixtures::Counted;
+
+        let count = core::cell::Cell::new(0);
+        let queue: VecDeque<_> = (0..100).map(|_| Counted(&count, false)).collect();
+        assert!(!crate::IsValid::is_valid(&queue));
+        assert_eq!(1, count.get());
+        count.set(0);
+        let context = Context::<()>::fail_fast().validate_at("queue", &queue);
+        assert_eq!(1, context.len());
+        assert_eq!(1, count.get());
+        count.set(0);
+        let map: BTreeMap<_, _> = (0..100).map(|key| (key, Counted(&count, false))).collect();
+        assert!(!crate::IsValid::is_valid(&map));
+        assert_eq!(1, count.get());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn validate_sequence_with_limit() {
+        let queue: VecDeque<_> = (0..100).map(|_| Leaf(false)).collect();
+        let context = Context::with_limit(1).validate_at("queue", &queue);
+        assert_eq!(1, context.len());
+        assert_eq!(99, context.dropped());
+        let paths: Vec<_> = context
+            .into_iter_with_paths()
+            .map(|(path, ())| path.to_string())
+            .collect();
+        assert_eq!(vec!["queue[0]"], paths);
+    }
+}
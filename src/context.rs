@@ -1,241 +1,2665 @@
 use super::*;
 
-use crate::smallvec::*;
+use crate::{
+    collections::Indexed,
+    environment::ValidateIn,
+    path::{Path, PathSegment},
+    sink::MapSink,
+};
 
-use core::iter::once;
+#[cfg(feature = "alloc")]
+use crate::smallvec::*;
 
-const SMALLVEC_ARRAY_LEN: usize = 8;
+use arrayvec::ArrayVec;
+use core::{
+    fmt,
+    iter::{once, Enumerate, FromIterator, Map},
+};
 
-type SmallVecArray<V> = [V; SMALLVEC_ARRAY_LEN];
+/// The default number of invalidities that are stored inline
+pub const DEFAULT_INLINE_CAPACITY: usize = 8;
 
-/// A collection of invalidities resulting from a validation
+/// The default accumulator of a [`Context`](struct.Context.html)
 ///
-/// Collects invalidities that are detected while performing
-/// a validation.
-#[derive(Clone, Debug, Default)]
-#[cfg_attr(test, derive(Eq, PartialEq))]
-pub struct Context<V>
-where
-    V: Invalidity,
-{
-    invalidities: SmallVec<SmallVecArray<V>>,
+/// Stores the invalidities together with their paths. Up to `N`
+/// invalidities are stored inline without allocating memory on
+/// the heap.
+///
+/// Only non-empty paths are stored separately together with the
+/// position of their invalidity, i.e. invalidities that are recorded
+/// directly within a context or by a context [without paths](struct.Context.html#method.without_paths)
+/// don't occupy any memory for their paths.
+///
+/// # Memory footprint
+///
+/// Each inline slot occupies `size_of::<V>()` bytes. The accumulator
+/// needs another 16 bytes for managing the slots and 24 bytes for the
+/// paths (on 64-bit platforms, including padding). Inline slots of small
+/// invalidity types may occupy the space that is reserved for managing
+/// the slots on the heap, e.g.
+///
+/// | `N` | `V = u8` | `V = [u8; 64]` |
+/// |-----|----------|----------------|
+/// | 0   | 48       | 48             |
+/// | 1   | 48       | 104            |
+/// | 2   | 48       | 168            |
+/// | 8   | 48       | 552            |
+///
+/// The [`Context`](struct.Context.html) adds another 64 bytes for recording
+/// warnings and for its configuration. Contexts are moved by value through
+/// all builder methods, i.e. a smaller inline capacity is preferable for
+/// large invalidity types, while a larger inline capacity avoids heap
+/// allocations for small invalidity types.
+///
+/// The results of [`Validate`](../trait.Validate.html) always use the
+/// [default inline capacity](constant.DEFAULT_INLINE_CAPACITY.html).
+///
+/// Without feature `alloc` the default accumulator is a
+/// [`FixedAccumulator`](struct.FixedAccumulator.html) with the same
+/// capacity.
+///
+/// # Example
+/// ```
+/// # use semval::{context::DefaultAccumulator, prelude::*};
+/// #[derive(Debug)]
+/// struct LargeInvalidity([u8; 64]);
+///
+/// let context = ValidationContext::<LargeInvalidity, DefaultAccumulator<_, 1>>::default()
+///     .invalidate(LargeInvalidity([0; 64]))
+///     .invalidate(LargeInvalidity([1; 64]));
+/// assert_eq!(2, context.into_iter().count());
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DefaultAccumulator<V, const N: usize = DEFAULT_INLINE_CAPACITY> {
+    invalidities: SmallVec<[V; N]>,
+    paths: SparsePaths,
 }
 
-impl<V> IsEmpty for Context<V>
-where
-    V: Invalidity,
-{
-    fn is_empty(&self) -> bool {
+#[cfg(feature = "alloc")]
+impl<V, const N: usize> DefaultAccumulator<V, N> {
+    /// Create an empty accumulator
+    pub fn new() -> Self {
+        Self {
+            invalidities: SmallVec::new(),
+            paths: Default::default(),
+        }
+    }
+
+    /// The number of stored invalidities
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.invalidities.len()
+    }
+
+    /// Check if no invalidities have been stored
+    #[inline]
+    pub fn is_empty(&self) -> bool {
         self.invalidities.is_empty()
     }
+
+    /// All stored invalidities together with their path
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&Path, &V)> + DoubleEndedIterator {
+        self.paths.iter_with(&self.invalidities)
+    }
+
+    fn push(&mut self, (path, invalidity): (Path, V)) {
+        self.paths.push(self.invalidities.len(), path);
+        self.invalidities.push(invalidity);
+    }
+
+    fn map<U>(self, map: impl Fn(V) -> U) -> DefaultAccumulator<U, N> {
+        let Self {
+            invalidities,
+            paths,
+        } = self;
+        DefaultAccumulator {
+            invalidities: invalidities.into_iter().map(map).collect(),
+            paths,
+        }
+    }
 }
 
-impl<V> Mergeable for Context<V>
-where
-    V: Invalidity,
-{
-    type Item = V;
+#[cfg(feature = "alloc")]
+impl<V, const N: usize> Default for DefaultAccumulator<V, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<V, const N: usize> IsEmpty for DefaultAccumulator<V, N> {
+    fn is_empty(&self) -> bool {
+        DefaultAccumulator::is_empty(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<V, const N: usize> Mergeable for DefaultAccumulator<V, N> {
+    type Item = (Path, V);
 
     fn empty<H>(capacity_hint: H) -> Self
     where
         H: Into<Option<usize>>,
     {
-        let invalidities = Mergeable::empty(capacity_hint);
-        Self { invalidities }
+        Self {
+            invalidities: Mergeable::empty(capacity_hint),
+            paths: Default::default(),
+        }
     }
 
     fn merge(mut self, other: Self) -> Self {
-        self.invalidities = self.invalidities.merge(other.invalidities);
+        let Self {
+            invalidities,
+            paths,
+        } = other;
+        self.paths.append(self.invalidities.len(), paths);
+        self.invalidities = self.invalidities.merge(invalidities);
         self
     }
 
-    fn merge_iter<H, I>(mut self, count_hint: H, iter: I) -> Self
+    fn merge_iter<H, I>(mut self, reserve_hint: H, iter: I) -> Self
     where
         H: Into<Option<usize>>,
         I: Iterator<Item = Self::Item>,
     {
-        self.invalidities = self.invalidities.merge_iter(count_hint, iter);
+        self.invalidities.reserve(reserve_hint.into().unwrap_or(0));
+        self.extend(iter);
         self
     }
 }
 
-impl<V> MergeableSized for Context<V> where V: Invalidity {}
+#[cfg(feature = "alloc")]
+impl<V, const N: usize> Accumulator<V> for DefaultAccumulator<V, N> {
+    fn item(path: Path, invalidity: V) -> Self::Item {
+        (path, invalidity)
+    }
+}
 
-impl<V> Context<V>
-where
-    V: Invalidity,
-{
-    /// Create a new valid and empty context
-    #[inline]
-    pub fn new() -> Self {
-        Self::empty(<SmallVecArray<V> as smallvec::Array>::size())
+#[cfg(feature = "alloc")]
+impl<V, const N: usize> Storage<V> for DefaultAccumulator<V, N> {
+    type IntoItems = IntoItems<IntoIter<[V; N]>>;
+
+    fn as_slice(&self) -> &[V] {
+        &self.invalidities
     }
 
-    /// Check if the context is still valid
-    #[inline]
-    pub fn is_valid(&self) -> bool {
-        self.is_empty()
+    fn path(&self, index: usize) -> &Path {
+        self.paths.get(index)
     }
 
-    /// Record a new invalidity within this context
-    #[inline]
-    pub fn invalidate(self, invalidity: impl Into<V>) -> Self {
-        self.merge_iter(1, once(invalidity.into()))
+    fn into_items(self) -> Self::IntoItems {
+        self.into_iter()
     }
 
-    /// Conditionally record a new invalidity within this context
-    #[inline]
-    pub fn invalidate_if(self, is_invalid: impl Into<bool>, invalidity: impl Into<V>) -> Self {
-        if is_invalid.into() {
-            self.invalidate(invalidity)
-        } else {
-            self
-        }
+    fn retain<F>(&mut self, predicate: F)
+    where
+        F: FnMut(&V) -> bool,
+    {
+        let invalidities = &mut self.invalidities;
+        self.paths
+            .retain(|retained| invalidities.retain(retained), predicate);
     }
+}
 
-    /// Merge the results of another validation
-    ///
-    /// Needed for collecting results from custom validation functions.
-    #[inline]
-    pub fn merge_result(self, res: Result<V>) -> Self {
-        if let Err(other) = res {
-            self.merge(other)
-        } else {
-            self
-        }
+/// Consume all stored invalidities together with their path
+#[cfg(feature = "alloc")]
+impl<V, const N: usize> IntoIterator for DefaultAccumulator<V, N> {
+    type Item = (Path, V);
+    type IntoIter = IntoItems<IntoIter<[V; N]>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoItems::new(self.invalidities.into_iter(), self.paths)
     }
+}
 
-    /// Merge the mapped results of another validation
-    ///
-    /// Needed for collecting results from custom validation functions.
-    pub fn merge_result_with<F, U>(self, res: Result<U>, map: F) -> Self
+/// Collect invalidities together with their path
+#[cfg(feature = "alloc")]
+impl<V, const N: usize> FromIterator<(Path, V)> for DefaultAccumulator<V, N> {
+    fn from_iter<I>(iter: I) -> Self
     where
-        F: Fn(U) -> V,
-        U: Invalidity,
+        I: IntoIterator<Item = (Path, V)>,
     {
-        if let Err(other) = res {
-            self.merge_exact_size_iter(other.invalidities.into_iter().map(map))
-        } else {
-            self
-        }
+        let mut accumulator = Self::new();
+        accumulator.extend(iter);
+        accumulator
     }
+}
 
-    /// Merge the mapped results of another validation
-    #[deprecated(
-        since = "0.2.0",
-        note = "Please use [`merge_result_with`](#method.merge_result_with) instead"
-    )]
-    pub fn map_and_merge_result<F, U>(self, res: Result<U>, map: F) -> Self
+#[cfg(feature = "alloc")]
+impl<V, const N: usize> Extend<(Path, V)> for DefaultAccumulator<V, N> {
+    fn extend<I>(&mut self, iter: I)
     where
-        F: Fn(U) -> V,
-        U: Invalidity,
+        I: IntoIterator<Item = (Path, V)>,
     {
-        if let Err(other) = res {
-            self.merge_exact_size_iter(other.invalidities.into_iter().map(map))
-        } else {
-            self
+        for item in iter {
+            self.push(item);
         }
     }
+}
 
-    /// Validate the target and merge the result into this context
-    #[inline]
-    pub fn validate<U>(self, target: &impl Validate<Invalidity = U>) -> Self
-    where
-        U: Invalidity + Into<V>,
-    {
-        self.validate_with(target, Into::into)
+/// The default accumulator of a [`Context`](struct.Context.html)
+///
+/// Stores up to `N` invalidities together with their paths without
+/// allocating any memory on the heap.
+#[cfg(not(feature = "alloc"))]
+pub type DefaultAccumulator<V, const N: usize = DEFAULT_INLINE_CAPACITY> = FixedAccumulator<V, N>;
+
+/// Accumulates all invalidities that are recorded as errors
+/// within a [`Context`](struct.Context.html)
+///
+/// Implemented for the [`DefaultAccumulator`](struct.DefaultAccumulator.html),
+/// for the [`FixedAccumulator`](struct.FixedAccumulator.html) with a fixed
+/// capacity, for `usize` that only counts the invalidities, and for `()`
+/// that discards them.
+///
+/// Only contexts that store invalidities together with their paths,
+/// i.e. with an accumulator that implements [`Storage`](trait.Storage.html),
+/// could be iterated, displayed, or serialized. The invalidities of nested
+/// validations are pushed directly into the accumulator of the context.
+pub trait Accumulator<V>: Mergeable {
+    /// Convert an invalidity and its path into an accumulated item
+    fn item(path: Path, invalidity: V) -> Self::Item;
+
+    /// Check if the accumulated items contain the paths of the invalidities
+    ///
+    /// Contexts don't record any paths for accumulators that discard them.
+    fn records_paths() -> bool {
+        true
     }
+}
 
-    /// Validate the target and merge the mapped result into this context
-    #[inline]
-    pub fn validate_with<F, U>(self, target: &impl Validate<Invalidity = U>, map: F) -> Self
-    where
-        F: Fn(U) -> V,
-        U: Invalidity,
-    {
-        self.merge_result_with(target.validate(), map)
+impl<V> Accumulator<V> for usize {
+    fn item(_: Path, _: V) -> Self::Item {
+        1
     }
 
-    /// Validate the target, map the result, and merge it into this context
-    #[deprecated(
-        since = "0.2.0",
-        note = "Please use [`validate_with`](#method.validate_with) instead"
-    )]
-    pub fn validate_and_map<F, U>(self, target: &impl Validate<Invalidity = U>, map: F) -> Self
-    where
-        F: Fn(U) -> V,
-        U: Invalidity,
-    {
-        self.validate_with(target, map)
+    fn records_paths() -> bool {
+        false
     }
+}
 
-    /// Finish the current validation of this context with a result
-    #[inline]
-    pub fn into_result(self) -> Result<V> {
-        if self.is_valid() {
-            Ok(())
-        } else {
-            Err(self)
+impl<V> Accumulator<V> for () {
+    fn item(_: Path, _: V) -> Self::Item {}
+
+    fn records_paths() -> bool {
+        false
+    }
+}
+
+/// An [`Accumulator`](trait.Accumulator.html) that stores all
+/// invalidities together with their paths in order
+///
+/// Implemented for the [`DefaultAccumulator`](struct.DefaultAccumulator.html)
+/// and the [`FixedAccumulator`](struct.FixedAccumulator.html).
+pub trait Storage<V>: Accumulator<V, Item = (Path, V)> {
+    /// Consuming iterator over all stored invalidities together with their path
+    type IntoItems: ExactSizeIterator<Item = (Path, V)> + DoubleEndedIterator;
+
+    /// All stored invalidities
+    fn as_slice(&self) -> &[V];
+
+    /// The path of the stored invalidity at the given position
+    ///
+    /// The path is empty if none has been recorded.
+    fn path(&self, index: usize) -> &Path;
+
+    /// Consume all stored invalidities together with their path
+    fn into_items(self) -> Self::IntoItems;
+
+    /// Retain only the invalidities that satisfy the predicate
+    fn retain<F>(&mut self, predicate: F)
+    where
+        F: FnMut(&V) -> bool;
+}
+
+// Paths are only recorded on targets with a heap allocator
+#[cfg(feature = "alloc")]
+type PathEntries = Vec<(usize, Path)>;
+
+#[cfg(not(feature = "alloc"))]
+type PathEntries = ArrayVec<(usize, Path), 0>;
+
+static EMPTY_PATH: Path = Path::new();
+
+/// The non-empty paths of stored invalidities
+///
+/// Most invalidities are recorded with an empty path. Only non-empty
+/// paths are stored together with the position of their invalidity
+/// in ascending order.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct SparsePaths(PathEntries);
+
+impl SparsePaths {
+    fn push(&mut self, index: usize, path: Path) {
+        if !path.is_empty() {
+            self.0.push((index, path));
         }
     }
+
+    fn get(&self, index: usize) -> &Path {
+        self.0
+            .binary_search_by_key(&index, |(index, _)| *index)
+            .map_or(&EMPTY_PATH, |pos| &self.0[pos].1)
+    }
+
+    fn iter_with<'a, V>(
+        &'a self,
+        invalidities: &'a [V],
+    ) -> impl ExactSizeIterator<Item = (&'a Path, &'a V)> + DoubleEndedIterator {
+        invalidities
+            .iter()
+            .enumerate()
+            .map(move |(index, invalidity)| (self.get(index), invalidity))
+    }
+
+    /// Append the paths of subsequent invalidities that start at
+    /// the given position
+    #[cfg(feature = "alloc")]
+    fn append(&mut self, offset: usize, other: Self) {
+        self.0.extend(
+            other
+                .0
+                .into_iter()
+                .map(|(index, path)| (offset + index, path)),
+        );
+    }
+
+    /// Retain the paths of all retained invalidities
+    ///
+    /// The invalidities are retained by `retain`, which must apply the
+    /// given predicate to all invalidities in order.
+    fn retain<V>(
+        &mut self,
+        retain: impl FnOnce(&mut dyn FnMut(&mut V) -> bool),
+        mut predicate: impl FnMut(&V) -> bool,
+    ) {
+        // The paths of removed invalidities are marked by an invalid position
+        const REMOVED: usize = usize::MAX;
+        let mut entries = self.0.iter_mut().peekable();
+        let mut index = 0;
+        let mut removed = 0;
+        retain(&mut |invalidity| {
+            let keep = predicate(invalidity);
+            if let Some(entry) = entries.next_if(|(entry_index, _)| *entry_index == index) {
+                entry.0 = if keep { index - removed } else { REMOVED };
+            }
+            if !keep {
+                removed += 1;
+            }
+            index += 1;
+            keep
+        });
+        self.0.retain(|(index, _)| *index != REMOVED);
+    }
 }
 
-impl<V> Into<Result<V>> for Context<V>
+/// Consuming iterator over stored invalidities together with their path
+///
+/// Returned by [`Storage::into_items`](trait.Storage.html#tymethod.into_items).
+#[derive(Debug)]
+pub struct IntoItems<I> {
+    invalidities: Enumerate<I>,
+    paths: <PathEntries as IntoIterator>::IntoIter,
+}
+
+impl<I> IntoItems<I>
 where
-    V: Invalidity,
+    I: Iterator,
 {
-    fn into(self) -> Result<V> {
-        self.into_result()
+    fn new(invalidities: I, paths: SparsePaths) -> Self {
+        Self {
+            invalidities: invalidities.enumerate(),
+            paths: paths.0.into_iter(),
+        }
     }
 }
 
-/// Transform the validation context into an iterator
-/// that yields all the collected invalidities.
-impl<V> IntoIterator for Context<V>
+impl<I> Iterator for IntoItems<I>
 where
-    V: Invalidity,
+    I: Iterator,
 {
-    type Item = V;
-    // TODO: Replace with an opaque, existential type eventually (if ever possible):
-    // type IntoIter = impl Iterator<V>;
-    type IntoIter = smallvec::IntoIter<SmallVecArray<V>>;
+    type Item = (Path, I::Item);
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.invalidities.into_iter()
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, invalidity) = self.invalidities.next()?;
+        let path = match self.paths.as_slice().first() {
+            Some((path_index, _)) if *path_index == index => {
+                self.paths.next().map(|(_, path)| path)
+            }
+            _ => None,
+        };
+        Some((path.unwrap_or_default(), invalidity))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.invalidities.size_hint()
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl<I> ExactSizeIterator for IntoItems<I> where I: ExactSizeIterator {}
 
-    #[test]
-    fn valid_context() {
-        let context = Context::<()>::new();
-        assert!(context.is_empty());
-        assert!(context.is_valid());
-        assert!(context.into_result().is_ok());
+impl<I> DoubleEndedIterator for IntoItems<I>
+where
+    I: ExactSizeIterator + DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (index, invalidity) = self.invalidities.next_back()?;
+        let path = match self.paths.as_slice().last() {
+            Some((path_index, _)) if *path_index == index => {
+                self.paths.next_back().map(|(_, path)| path)
+            }
+            _ => None,
+        };
+        Some((path.unwrap_or_default(), invalidity))
     }
+}
 
-    #[test]
-    fn default_context() {
-        assert_eq!(Context::<()>::new(), Context::<()>::default());
+/// An accumulator with a fixed capacity
+///
+/// Stores up to `N` invalidities together with their paths inline. All
+/// further invalidities are discarded and only counted as
+/// [overflowed](#method.overflowed). The context still counts them
+/// as errors, i.e. an overflow never turns an invalid context into a
+/// valid one.
+///
+/// The accumulator itself never allocates. If feature `alloc` is enabled
+/// non-empty [paths](../path/struct.Path.html) and [warnings](struct.Context.html#method.warn)
+/// are still allocated on the heap, as well as the results of nested
+/// validations that don't implement [`Validate::validate_into`](../trait.Validate.html#method.validate_into).
+///
+/// # Heapless targets
+///
+/// Without feature `alloc` the crate doesn't depend on a heap allocator
+/// at all. The fixed accumulator becomes the [`DefaultAccumulator`](type.DefaultAccumulator.html),
+/// paths are not recorded, and at most [`DEFAULT_INLINE_CAPACITY`](constant.DEFAULT_INLINE_CAPACITY.html)
+/// warnings are stored.
+///
+/// # Example
+/// ```
+/// # use semval::{context::FixedAccumulator, prelude::*};
+/// let context = ValidationContext::<&str, FixedAccumulator<_, 2>>::default()
+///     .invalidate("first")
+///     .invalidate("second")
+///     .invalidate("third");
+/// assert!(!context.is_valid());
+/// let invalidities = context.into_accumulator();
+/// assert_eq!(1, invalidities.overflowed());
+/// assert_eq!(
+///     vec!["first", "second"],
+///     invalidities.iter().map(|(_, invalidity)| *invalidity).collect::<Vec<_>>()
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FixedAccumulator<V, const N: usize> {
+    invalidities: ArrayVec<V, N>,
+    paths: SparsePaths,
+    overflowed: usize,
+}
+
+impl<V, const N: usize> FixedAccumulator<V, N> {
+    /// Create an empty accumulator
+    pub fn new() -> Self {
+        Self {
+            invalidities: ArrayVec::new(),
+            paths: Default::default(),
+            overflowed: 0,
+        }
     }
 
-    #[test]
-    fn invalidate() {
-        let mut context = Context::<()>::new();
-        assert!(context.is_empty());
-        assert!(context.is_valid());
-        for _ in 0..=SMALLVEC_ARRAY_LEN {
-            let invalidities_before = context.invalidities.len();
-            context = context.invalidate(());
-            assert!(!context.is_empty());
-            assert!(!context.is_valid());
-            let invalidities_after = context.invalidities.len();
-            assert_eq!(invalidities_after, invalidities_before + 1);
+    /// The number of stored invalidities
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.invalidities.len()
+    }
+
+    /// Check if no invalidities have been accumulated, neither
+    /// stored nor overflowed
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.invalidities.is_empty() && self.overflowed == 0
+    }
+
+    /// Check if no more invalidities could be stored
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.invalidities.is_full()
+    }
+
+    /// The number of invalidities that have been discarded
+    /// after the capacity has been exhausted
+    #[inline]
+    pub fn overflowed(&self) -> usize {
+        self.overflowed
+    }
+
+    /// All stored invalidities together with their path
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&Path, &V)> + DoubleEndedIterator {
+        self.paths.iter_with(&self.invalidities)
+    }
+
+    fn push(&mut self, (path, invalidity): (Path, V)) {
+        if self.invalidities.is_full() {
+            self.overflowed += 1;
+        } else {
+            self.paths.push(self.invalidities.len(), path);
+            self.invalidities.push(invalidity);
         }
-        assert_eq!(SMALLVEC_ARRAY_LEN + 1, context.invalidities.len());
-        assert!(context.into_result().is_err());
+    }
+
+    fn map<U>(self, map: impl Fn(V) -> U) -> FixedAccumulator<U, N> {
+        let Self {
+            invalidities,
+            paths,
+            overflowed,
+        } = self;
+        FixedAccumulator {
+            invalidities: invalidities.into_iter().map(map).collect(),
+            paths,
+            overflowed,
+        }
+    }
+}
+
+impl<V, const N: usize> Default for FixedAccumulator<V, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V, const N: usize> IsEmpty for FixedAccumulator<V, N> {
+    fn is_empty(&self) -> bool {
+        FixedAccumulator::is_empty(self)
+    }
+}
+
+impl<V, const N: usize> Mergeable for FixedAccumulator<V, N> {
+    type Item = (Path, V);
+
+    fn empty<H>(_: H) -> Self
+    where
+        H: Into<Option<usize>>,
+    {
+        Self::new()
+    }
+
+    fn merge(self, other: Self) -> Self {
+        let overflowed = other.overflowed;
+        let mut merged = self.merge_iter(None, other.into_iter());
+        merged.overflowed += overflowed;
+        merged
+    }
+
+    fn merge_iter<H, I>(mut self, _: H, iter: I) -> Self
+    where
+        H: Into<Option<usize>>,
+        I: Iterator<Item = Self::Item>,
+    {
+        self.extend(iter);
+        self
+    }
+}
+
+impl<V, const N: usize> Accumulator<V> for FixedAccumulator<V, N> {
+    fn item(path: Path, invalidity: V) -> Self::Item {
+        (path, invalidity)
+    }
+}
+
+impl<V, const N: usize> Storage<V> for FixedAccumulator<V, N> {
+    type IntoItems = IntoItems<arrayvec::IntoIter<V, N>>;
+
+    fn as_slice(&self) -> &[V] {
+        &self.invalidities
+    }
+
+    fn path(&self, index: usize) -> &Path {
+        self.paths.get(index)
+    }
+
+    fn into_items(self) -> Self::IntoItems {
+        self.into_iter()
+    }
+
+    fn retain<F>(&mut self, predicate: F)
+    where
+        F: FnMut(&V) -> bool,
+    {
+        let invalidities = &mut self.invalidities;
+        self.paths
+            .retain(|retained| invalidities.retain(retained), predicate);
+    }
+}
+
+/// Consume all stored invalidities together with their path
+///
+/// Overflowed invalidities are not yielded.
+impl<V, const N: usize> IntoIterator for FixedAccumulator<V, N> {
+    type Item = (Path, V);
+    type IntoIter = IntoItems<arrayvec::IntoIter<V, N>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoItems::new(self.invalidities.into_iter(), self.paths)
+    }
+}
+
+/// Collect invalidities together with their path
+///
+/// All invalidities that exceed the capacity are counted as
+/// [overflowed](struct.FixedAccumulator.html#method.overflowed).
+impl<V, const N: usize> FromIterator<(Path, V)> for FixedAccumulator<V, N> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (Path, V)>,
+    {
+        let mut accumulator = Self::new();
+        accumulator.extend(iter);
+        accumulator
+    }
+}
+
+impl<V, const N: usize> Extend<(Path, V)> for FixedAccumulator<V, N> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (Path, V)>,
+    {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+// Warnings are rare and are only allocated on demand
+#[cfg(feature = "alloc")]
+type WarningItems<V> = Vec<(Path, V)>;
+
+#[cfg(not(feature = "alloc"))]
+type WarningItems<V> = ArrayVec<(Path, V), DEFAULT_INLINE_CAPACITY>;
+
+/// The warnings of a validation
+///
+/// Carried by the `Ok` variant of a [`Result`](../type.Result.html),
+/// while the `Err` variant carries them within the [`Context`](struct.Context.html).
+/// Nested validations pass the warnings on to their parent in both cases.
+///
+/// Without feature `alloc` at most [`DEFAULT_INLINE_CAPACITY`](constant.DEFAULT_INLINE_CAPACITY.html)
+/// warnings are stored.
+#[derive(Clone, Debug)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+pub struct Warnings<V> {
+    items: WarningItems<V>,
+}
+
+impl<V> Default for Warnings<V> {
+    fn default() -> Self {
+        Self {
+            items: Default::default(),
+        }
+    }
+}
+
+impl<V> Warnings<V> {
+    /// Check if no warnings have been recorded
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The number of recorded warnings
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// All recorded warnings together with their path
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&Path, &V)> + DoubleEndedIterator {
+        self.items
+            .iter()
+            .map(|(path, invalidity)| (path, invalidity))
+    }
+
+    /// Push all warnings into a sink
+    pub fn merge_into(self, sink: &mut dyn InvaliditySink<V>) {
+        for (path, invalidity) in self.items {
+            sink.add_warning(path, invalidity);
+        }
+    }
+
+    fn push(&mut self, item: (Path, V)) {
+        #[cfg(feature = "alloc")]
+        self.items.push(item);
+        // Without a heap allocator all further warnings are discarded
+        #[cfg(not(feature = "alloc"))]
+        let _ = self.items.try_push(item);
+    }
+
+    fn retain(&mut self, mut predicate: impl FnMut(&V) -> bool) {
+        self.items.retain(|(_, invalidity)| predicate(invalidity));
+    }
+
+    fn map<U>(self, map: impl Fn(V) -> U) -> Warnings<U> {
+        Warnings {
+            items: self
+                .items
+                .into_iter()
+                .map(|(path, invalidity)| (path, map(invalidity)))
+                .collect(),
+        }
+    }
+}
+
+/// Consume all recorded warnings together with their path
+impl<V> IntoIterator for Warnings<V> {
+    type Item = (Path, V);
+    type IntoIter = <WarningItems<V> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+/// A collection of invalidities resulting from a validation
+///
+/// Collects invalidities that are detected while performing
+/// a validation.
+///
+/// Each invalidity is recorded together with its [`Path`](../path/struct.Path.html)
+/// within the validated target. Paths are only recorded by nested validations
+/// that specify a path segment, i.e. [`validate_at`](#method.validate_at) and
+/// [`validate_at_with`](#method.validate_at_with). Otherwise the path remains
+/// empty.
+///
+/// A [fail-fast](#method.fail_fast) context stops collecting invalidities
+/// after the first one has been recorded. A context [with a limit](#method.with_limit)
+/// only counts all further invalidities after the limit has been reached.
+///
+/// Invalidities are recorded as errors unless they are recorded as
+/// [warnings](#method.warn). Warnings are advisory and don't affect the
+/// validity of the context. They are neither yielded when iterating over
+/// the context nor displayed, but are accessible separately by
+/// [`warnings`](#method.warnings). A successful [`Result`](../type.Result.html)
+/// carries the [`Warnings`](struct.Warnings.html) of the context.
+///
+/// All errors are collected by an [`Accumulator`](trait.Accumulator.html).
+/// By default all invalidities are stored together with their paths, but
+/// contexts that only need to count or detect invalidities may choose a
+/// different accumulator, e.g. `Context<V, usize>` or `Context<V, ()>`.
+///
+/// Contexts are typically passed by value through a chain of builder
+/// methods. Mutably borrowed contexts could be modified in place by
+/// [`push`](#method.push), [`check`](#method.check), [`nested`](#method.nested),
+/// and [`nested_at`](#method.nested_at) instead.
+#[derive(Clone, Debug)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
+pub struct Context<V, A = DefaultAccumulator<V>>
+where
+    V: Invalidity,
+    A: Accumulator<V>,
+{
+    invalidities: A,
+    len: usize,
+    warnings: Warnings<V>,
+    fail_fast: bool,
+    limit: Option<usize>,
+    paths: bool,
+    dropped: usize,
+}
+
+impl<V, A> Default for Context<V, A>
+where
+    V: Invalidity,
+    A: Accumulator<V>,
+{
+    fn default() -> Self {
+        Self {
+            invalidities: A::empty(None),
+            len: 0,
+            warnings: Default::default(),
+            fail_fast: false,
+            limit: None,
+            paths: cfg!(feature = "alloc") && A::records_paths(),
+            dropped: 0,
+        }
+    }
+}
+
+impl<V, A> IsEmpty for Context<V, A>
+where
+    V: Invalidity,
+    A: Accumulator<V>,
+{
+    fn is_empty(&self) -> bool {
+        self.len == 0 && self.dropped == 0
+    }
+}
+
+impl<V, A> Mergeable for Context<V, A>
+where
+    V: Invalidity,
+    A: Accumulator<V> + IntoIterator<Item = <A as Mergeable>::Item>,
+{
+    type Item = (Path, V);
+
+    fn empty<H>(capacity_hint: H) -> Self
+    where
+        H: Into<Option<usize>>,
+    {
+        Self {
+            invalidities: A::empty(capacity_hint),
+            ..Default::default()
+        }
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        if self.is_done() {
+            return self;
+        }
+        let Context {
+            invalidities,
+            len,
+            warnings,
+            dropped,
+            ..
+        } = other;
+        match self.remaining() {
+            Some(remaining) if remaining < len => {
+                self.invalidities = self
+                    .invalidities
+                    .merge_iter(remaining, invalidities.into_iter().take(remaining));
+                self.len += remaining;
+                self.merge_dropped(len - remaining + dropped);
+            }
+            _ => {
+                self.invalidities = self.invalidities.merge(invalidities);
+                self.len += len;
+                self.merge_dropped(dropped);
+            }
+        }
+        self.merge_warnings(warnings.into_iter());
+        self
+    }
+
+    fn merge_iter<H, I>(mut self, count_hint: H, iter: I) -> Self
+    where
+        H: Into<Option<usize>>,
+        I: Iterator<Item = Self::Item>,
+    {
+        self.record(count_hint, iter);
+        self
+    }
+}
+
+impl<V, A> MergeableSized for Context<V, A>
+where
+    V: Invalidity,
+    A: Accumulator<V> + IntoIterator<Item = <A as Mergeable>::Item>,
+{
+}
+
+impl<V> Context<V>
+where
+    V: Invalidity,
+{
+    /// Create a new valid and empty context
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create a new valid and empty context that stops collecting
+    /// invalidities after the first one
+    ///
+    /// All further invalidities are ignored and nested validations are
+    /// skipped once the context has become invalid. Use this mode if
+    /// only the outcome of a validation is of interest, e.g. for
+    /// [`IsValid`](../trait.IsValid.html).
+    #[inline]
+    pub fn fail_fast() -> Self {
+        Self {
+            fail_fast: true,
+            ..Self::new()
+        }
+    }
+
+    /// Create a new valid and empty context that stores at most
+    /// `limit` invalidities
+    ///
+    /// Further invalidities are not stored but counted, see
+    /// [`dropped`](#method.dropped). The limit bounds the memory
+    /// that is needed for validating large amounts of untrusted
+    /// data.
+    ///
+    /// The limit applies to all nested validations, because their
+    /// invalidities are pushed directly into this context.
+    #[inline]
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Self::new()
+        }
+    }
+}
+
+impl<V, A> Context<V, A>
+where
+    V: Invalidity,
+    A: Accumulator<V>,
+{
+    /// Create a new valid and empty context with a custom accumulator
+    ///
+    /// # Panics
+    ///
+    /// Panics if the accumulator is not empty.
+    #[inline]
+    pub fn with_accumulator(accumulator: A) -> Self
+    where
+        A: IsEmpty,
+    {
+        assert!(accumulator.is_empty(), "accumulator is not empty");
+        Self {
+            invalidities: accumulator,
+            ..Default::default()
+        }
+    }
+
+    /// Stop recording the paths of invalidities
+    ///
+    /// All invalidities are recorded with an empty path. Nested validations
+    /// then skip building paths that are not needed, e.g. if only the
+    /// invalidities themselves are reported.
+    #[inline]
+    pub fn without_paths(mut self) -> Self {
+        self.paths = false;
+        self
+    }
+
+    /// Check if the context records the paths of invalidities
+    ///
+    /// Paths are recorded by default unless the accumulator discards them
+    /// or feature `alloc` is disabled.
+    #[inline]
+    pub fn records_paths(&self) -> bool {
+        self.paths
+    }
+
+    /// Check if the context stops collecting invalidities after the first one
+    #[inline]
+    pub fn is_fail_fast(&self) -> bool {
+        self.fail_fast
+    }
+
+    /// The maximum number of stored invalidities, if any
+    #[inline]
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// The number of invalidities that have been dropped after
+    /// reaching the [limit](#method.with_limit)
+    ///
+    /// A context with dropped invalidities is invalid, even if no
+    /// invalidities have been stored.
+    #[inline]
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// The number of invalidities that could still be stored, if limited
+    fn remaining(&self) -> Option<usize> {
+        let capacity = if self.fail_fast {
+            Some(self.limit.unwrap_or(1).min(1))
+        } else {
+            self.limit
+        };
+        capacity.map(|capacity| capacity.saturating_sub(self.len))
+    }
+
+    /// Record invalidities as errors, respecting the mode and the limit
+    fn record<H, I>(&mut self, count_hint: H, iter: I)
+    where
+        H: Into<Option<usize>>,
+        I: Iterator<Item = (Path, V)>,
+    {
+        if self.is_done() {
+            return;
+        }
+        let limit = self.remaining().unwrap_or(usize::MAX);
+        let reserve_hint = count_hint.into().map(|count| count.min(limit));
+        let paths = self.paths;
+        let mut iter = iter;
+        let mut count = 0;
+        {
+            let mut recorded = iter.by_ref().take(limit);
+            let invalidities = core::mem::replace(&mut self.invalidities, A::empty(None));
+            self.invalidities = invalidities.merge_iter(
+                reserve_hint,
+                recorded.by_ref().map(|(path, invalidity)| {
+                    count += 1;
+                    A::item(if paths { path } else { Path::new() }, invalidity)
+                }),
+            );
+            // Accumulators may ignore items
+            count += recorded.count();
+        }
+        self.len += count;
+        let dropped = iter.count();
+        self.merge_dropped(dropped);
+    }
+
+    fn map_with<U, B>(
+        self,
+        map_invalidities: impl FnOnce(A) -> B,
+        map: impl Fn(V) -> U,
+    ) -> Context<U, B>
+    where
+        U: Invalidity,
+        B: Accumulator<U>,
+    {
+        let Context {
+            invalidities,
+            len,
+            warnings,
+            fail_fast,
+            limit,
+            paths,
+            dropped,
+        } = self;
+        Context {
+            invalidities: map_invalidities(invalidities),
+            len,
+            warnings: warnings.map(map),
+            fail_fast,
+            limit,
+            paths,
+            dropped,
+        }
+    }
+
+    fn merge_dropped(&mut self, dropped: usize) {
+        // Invalidities are only counted if the validation continues
+        if !self.fail_fast {
+            self.dropped += dropped;
+        }
+    }
+
+    /// Check if the context is still valid
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.is_empty()
+    }
+
+    /// The number of invalidities that have been recorded as errors,
+    /// excluding all [dropped](#method.dropped) invalidities
+    #[inline]
+    #[allow(clippy::len_without_is_empty)] // see is_valid()
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The accumulated errors
+    #[inline]
+    pub fn accumulator(&self) -> &A {
+        &self.invalidities
+    }
+
+    /// Unwrap the accumulated errors
+    #[inline]
+    pub fn into_accumulator(self) -> A {
+        self.invalidities
+    }
+
+    /// Record a new invalidity within this context
+    #[inline]
+    pub fn invalidate(mut self, invalidity: impl Into<V>) -> Self {
+        self.push(invalidity);
+        self
+    }
+
+    /// Record a new invalidity in place
+    ///
+    /// The in-place counterpart of [`invalidate`](#method.invalidate)
+    /// for mutably borrowed contexts, e.g. within loops or conditional
+    /// branches.
+    pub fn push(&mut self, invalidity: impl Into<V>) -> &mut Self {
+        self.record(1, once((Path::new(), invalidity.into())));
+        self
+    }
+
+    /// Conditionally record a new invalidity in place
+    ///
+    /// The in-place counterpart of [`invalidate_if`](#method.invalidate_if).
+    pub fn check(&mut self, is_invalid: impl Into<bool>, invalidity: impl Into<V>) -> &mut Self {
+        if is_invalid.into() {
+            self.push(invalidity)
+        } else {
+            self
+        }
+    }
+
+    /// Validate the target and merge the mapped result in place
+    ///
+    /// The in-place counterpart of [`validate_with`](#method.validate_with).
+    pub fn nested<F, U>(&mut self, target: &impl Validate<Invalidity = U>, map: F) -> &mut Self
+    where
+        F: Fn(U) -> V,
+        U: Invalidity,
+    {
+        if self.is_done() {
+            return self;
+        }
+        target.validate_into(&mut MapSink::new(self, map));
+        self
+    }
+
+    /// Validate the target and merge the mapped result in place
+    /// at the given path segment
+    ///
+    /// The in-place counterpart of [`validate_at_with`](#method.validate_at_with).
+    pub fn nested_at<F, U>(
+        &mut self,
+        segment: impl Into<PathSegment>,
+        target: &impl Validate<Invalidity = U>,
+        map: F,
+    ) -> &mut Self
+    where
+        F: Fn(U) -> V,
+        U: Invalidity,
+    {
+        if self.is_done() {
+            return self;
+        }
+        target.validate_into(&mut PrefixSink::new(
+            &mut MapSink::new(self, map),
+            segment.into(),
+        ));
+        self
+    }
+
+    /// Conditionally record a new invalidity within this context
+    #[inline]
+    pub fn invalidate_if(self, is_invalid: impl Into<bool>, invalidity: impl Into<V>) -> Self {
+        if is_invalid.into() {
+            self.invalidate(invalidity)
+        } else {
+            self
+        }
+    }
+
+    /// Record a new warning within this context
+    ///
+    /// Warnings don't affect the validity of the context. They are
+    /// passed on to a parent context by nested validations, regardless
+    /// of whether the nested validation succeeded or failed.
+    #[inline]
+    pub fn warn(mut self, invalidity: impl Into<V>) -> Self {
+        self.merge_warnings(once((Path::new(), invalidity.into())));
+        self
+    }
+
+    /// Conditionally record a new warning within this context
+    #[inline]
+    pub fn warn_if(self, is_suspicious: impl Into<bool>, invalidity: impl Into<V>) -> Self {
+        if is_suspicious.into() {
+            self.warn(invalidity)
+        } else {
+            self
+        }
+    }
+
+    /// Check if any warnings have been recorded
+    #[inline]
+    pub fn has_warnings(&self) -> bool {
+        !self.warnings.is_empty()
+    }
+
+    /// All recorded warnings together with their path
+    pub fn warnings(&self) -> impl ExactSizeIterator<Item = (&Path, &V)> + DoubleEndedIterator {
+        self.warnings.iter()
+    }
+
+    fn merge_warnings(&mut self, warnings: impl Iterator<Item = (Path, V)>) {
+        if self.fail_fast {
+            // Warnings don't affect the outcome
+            return;
+        }
+        let remaining = self.limit.map_or(usize::MAX, |limit| {
+            limit.saturating_sub(self.warnings.len())
+        });
+        for (path, invalidity) in warnings.take(remaining) {
+            let path = if self.paths { path } else { Path::new() };
+            self.warnings.push((path, invalidity));
+        }
+    }
+
+    /// Merge the results of another validation
+    ///
+    /// Needed for collecting results from custom validation functions.
+    #[inline]
+    pub fn merge_result(mut self, res: Result<V>) -> Self {
+        self.merge_mapped_result(res, core::convert::identity);
+        self
+    }
+
+    /// Merge the mapped results of another validation
+    ///
+    /// Needed for collecting results from custom validation functions.
+    pub fn merge_result_with<F, U>(mut self, res: Result<U>, map: F) -> Self
+    where
+        F: Fn(U) -> V,
+        U: Invalidity,
+    {
+        self.merge_mapped_result(res, |(path, invalidity)| (path, map(invalidity)));
+        self
+    }
+
+    /// Merge the mapped results of a nested validation at the
+    /// given path segment
+    #[cfg(feature = "async")]
+    pub(crate) fn merge_result_at_with<F, U>(
+        mut self,
+        segment: PathSegment,
+        res: Result<U>,
+        map: F,
+    ) -> Self
+    where
+        F: Fn(U) -> V,
+        U: Invalidity,
+    {
+        let paths = self.paths;
+        self.merge_mapped_result(res, |(path, invalidity)| {
+            let path = if paths { path.prefixed(segment) } else { path };
+            (path, map(invalidity))
+        });
+        self
+    }
+
+    fn merge_mapped_result<F, U>(&mut self, res: Result<U>, map: F)
+    where
+        F: Fn((Path, U)) -> (Path, V),
+        U: Invalidity,
+    {
+        match res {
+            Ok(warnings) => self.merge_warnings(warnings.into_iter().map(&map)),
+            Err(other) => {
+                let Context {
+                    invalidities,
+                    len,
+                    warnings,
+                    dropped,
+                    ..
+                } = other;
+                // Invalidities that overflowed the accumulator are merged as dropped
+                let count = invalidities.as_slice().len();
+                self.record(count, invalidities.into_items().map(&map));
+                self.merge_dropped(dropped + len - count);
+                self.merge_warnings(warnings.into_iter().map(&map));
+            }
+        }
+    }
+
+    /// Merge the mapped results of another validation
+    #[deprecated(
+        since = "0.2.0",
+        note = "Please use [`merge_result_with`](#method.merge_result_with) instead"
+    )]
+    pub fn map_and_merge_result<F, U>(self, res: Result<U>, map: F) -> Self
+    where
+        F: Fn(U) -> V,
+        U: Invalidity,
+    {
+        self.merge_result_with(res, map)
+    }
+
+    /// Validate the target and merge the result into this context
+    #[inline]
+    pub fn validate<U>(self, target: &impl Validate<Invalidity = U>) -> Self
+    where
+        U: Invalidity + Into<V>,
+    {
+        self.validate_with(target, Into::into)
+    }
+
+    /// Validate the target and merge the mapped result into this context
+    #[inline]
+    pub fn validate_with<F, U>(mut self, target: &impl Validate<Invalidity = U>, map: F) -> Self
+    where
+        F: Fn(U) -> V,
+        U: Invalidity,
+    {
+        self.nested(target, map);
+        self
+    }
+
+    /// Validate the target and merge the result into this context
+    /// at the given path segment
+    ///
+    /// The segment is typically the name of a field or the index of
+    /// an element, i.e. the role of the target within the current context.
+    #[inline]
+    pub fn validate_at<U>(
+        self,
+        segment: impl Into<PathSegment>,
+        target: &impl Validate<Invalidity = U>,
+    ) -> Self
+    where
+        U: Invalidity + Into<V>,
+    {
+        self.validate_at_with(segment, target, Into::into)
+    }
+
+    /// Validate the target and merge the mapped result into this context
+    /// at the given path segment
+    #[inline]
+    pub fn validate_at_with<F, U>(
+        mut self,
+        segment: impl Into<PathSegment>,
+        target: &impl Validate<Invalidity = U>,
+        map: F,
+    ) -> Self
+    where
+        F: Fn(U) -> V,
+        U: Invalidity,
+    {
+        self.nested_at(segment, target, map);
+        self
+    }
+
+    /// Validate the target within an environment and merge the result
+    /// into this context
+    #[inline]
+    pub fn validate_in<E, U>(self, target: &impl ValidateIn<E, Invalidity = U>, env: &E) -> Self
+    where
+        E: ?Sized,
+        U: Invalidity + Into<V>,
+    {
+        self.validate_in_with(target, env, Into::into)
+    }
+
+    /// Validate the target within an environment and merge the mapped
+    /// result into this context
+    pub fn validate_in_with<E, F, U>(
+        mut self,
+        target: &impl ValidateIn<E, Invalidity = U>,
+        env: &E,
+        map: F,
+    ) -> Self
+    where
+        E: ?Sized,
+        F: Fn(U) -> V,
+        U: Invalidity,
+    {
+        if self.is_done() {
+            return self;
+        }
+        target.validate_in_into(env, &mut MapSink::new(&mut self, map));
+        self
+    }
+
+    /// Validate the target within an environment and merge the result
+    /// into this context at the given path segment
+    #[inline]
+    pub fn validate_at_in<E, U>(
+        self,
+        segment: impl Into<PathSegment>,
+        target: &impl ValidateIn<E, Invalidity = U>,
+        env: &E,
+    ) -> Self
+    where
+        E: ?Sized,
+        U: Invalidity + Into<V>,
+    {
+        self.validate_at_in_with(segment, target, env, Into::into)
+    }
+
+    /// Validate the target within an environment and merge the mapped
+    /// result into this context at the given path segment
+    pub fn validate_at_in_with<E, F, U>(
+        mut self,
+        segment: impl Into<PathSegment>,
+        target: &impl ValidateIn<E, Invalidity = U>,
+        env: &E,
+        map: F,
+    ) -> Self
+    where
+        E: ?Sized,
+        F: Fn(U) -> V,
+        U: Invalidity,
+    {
+        if self.is_done() {
+            return self;
+        }
+        target.validate_in_into(
+            env,
+            &mut PrefixSink::new(&mut MapSink::new(&mut self, map), segment.into()),
+        );
+        self
+    }
+
+    /// Validate all targets and merge the results into this context
+    /// together with the position of each target
+    ///
+    /// In contrast to validating a slice or `Vec` as a whole the invalidities
+    /// are wrapped into [`Indexed`](../collections/struct.Indexed.html) to
+    /// identify the failed elements.
+    #[inline]
+    pub fn validate_each<I, U>(self, targets: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Validate<Invalidity = U>,
+        U: Invalidity,
+        Indexed<U>: Into<V>,
+    {
+        self.validate_each_with(targets, Into::into)
+    }
+
+    /// Validate all targets and merge the mapped results into this context
+    /// together with the position of each target
+    pub fn validate_each_with<I, F, U>(self, targets: I, map: F) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Validate<Invalidity = U>,
+        F: Fn(Indexed<U>) -> V,
+        U: Invalidity,
+    {
+        targets
+            .into_iter()
+            .enumerate()
+            .fold(self, |ctx, (index, target)| {
+                ctx.validate_at_with(index, &target, |invalidity| {
+                    map(Indexed::new(index, invalidity))
+                })
+            })
+    }
+
+    /// Validate the target, map the result, and merge it into this context
+    #[deprecated(
+        since = "0.2.0",
+        note = "Please use [`validate_with`](#method.validate_with) instead"
+    )]
+    pub fn validate_and_map<F, U>(self, target: &impl Validate<Invalidity = U>, map: F) -> Self
+    where
+        F: Fn(U) -> V,
+        U: Invalidity,
+    {
+        self.validate_with(target, map)
+    }
+
+    /// Finish the current validation of this context with a result
+    ///
+    /// The result is only an error if at least one invalidity has been
+    /// recorded as an error. Otherwise it carries all warnings.
+    #[inline]
+    pub fn into_result(self) -> Result<V, A> {
+        if self.is_valid() {
+            Ok(self.warnings)
+        } else {
+            Err(self)
+        }
+    }
+}
+
+/// Contexts are the root sinks of validations.
+impl<V, A> InvaliditySink<V> for Context<V, A>
+where
+    V: Invalidity,
+    A: Accumulator<V>,
+{
+    fn add_invalidity(&mut self, path: Path, invalidity: V) {
+        self.record(1, once((path, invalidity)));
+    }
+
+    fn add_warning(&mut self, path: Path, invalidity: V) {
+        self.merge_warnings(once((path, invalidity)));
+    }
+
+    fn add_dropped(&mut self, count: usize) {
+        self.merge_dropped(count);
+    }
+
+    #[inline]
+    fn is_done(&self) -> bool {
+        self.fail_fast && !self.is_valid()
+    }
+
+    #[inline]
+    fn records_paths(&self) -> bool {
+        self.paths
+    }
+}
+
+impl<V, A> Context<V, A>
+where
+    V: Invalidity,
+    A: Storage<V>,
+{
+    /// Push all errors and warnings of this context into a sink
+    ///
+    /// Needed for passing on the results of validations that are not
+    /// aware of sinks.
+    pub fn merge_into(self, sink: &mut dyn InvaliditySink<V>) {
+        let Context {
+            invalidities,
+            len,
+            warnings,
+            dropped,
+            ..
+        } = self;
+        // Invalidities that overflowed the accumulator are passed on as dropped
+        let overflowed = len - invalidities.as_slice().len();
+        for (path, invalidity) in invalidities.into_items() {
+            sink.add_invalidity(path, invalidity);
+        }
+        sink.add_dropped(dropped + overflowed);
+        for (path, invalidity) in warnings {
+            sink.add_warning(path, invalidity);
+        }
+    }
+
+    /// Transform the validation context into an iterator that
+    /// yields all the collected invalidities together with their path
+    pub fn into_iter_with_paths(
+        self,
+    ) -> impl ExactSizeIterator<Item = (Path, V)> + DoubleEndedIterator {
+        self.invalidities.into_items()
+    }
+
+    /// All invalidities that have been recorded as errors
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &V> + DoubleEndedIterator {
+        self.invalidities.as_slice().iter()
+    }
+
+    /// All invalidities that have been recorded as errors together
+    /// with their paths
+    pub fn iter_with_paths(
+        &self,
+    ) -> impl ExactSizeIterator<Item = (&Path, &V)> + DoubleEndedIterator {
+        self.invalidities
+            .as_slice()
+            .iter()
+            .enumerate()
+            .map(move |(index, invalidity)| (self.invalidities.path(index), invalidity))
+    }
+
+    /// The first invalidity that has been recorded as an error
+    pub fn first(&self) -> Option<&V> {
+        self.iter().next()
+    }
+
+    /// Check if an invalidity has been recorded as an error
+    pub fn contains(&self, invalidity: &V) -> bool
+    where
+        V: PartialEq,
+    {
+        self.iter().any(|recorded| recorded == invalidity)
+    }
+
+    /// Retain only the invalidities that satisfy the predicate
+    ///
+    /// Both errors and warnings are filtered in place while preserving
+    /// their order. [Dropped](#method.dropped) and overflowed invalidities
+    /// are still counted, i.e. the context remains invalid.
+    pub fn retain<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(&V) -> bool,
+    {
+        // Invalidities that overflowed the accumulator could not be tested
+        let overflowed = self.len - self.invalidities.as_slice().len();
+        self.invalidities.retain(&mut predicate);
+        self.len = self.invalidities.as_slice().len() + overflowed;
+        self.warnings.retain(predicate);
+    }
+
+    /// Filter the invalidities by a predicate
+    ///
+    /// The consuming counterpart of [`retain`](#method.retain).
+    pub fn filter<F>(mut self, predicate: F) -> Self
+    where
+        F: FnMut(&V) -> bool,
+    {
+        self.retain(predicate);
+        self
+    }
+
+    /// Split the invalidities into two contexts
+    ///
+    /// The first context contains all errors and warnings that satisfy
+    /// the predicate and the second context all others. Both contexts
+    /// inherit the mode and the limit of this context. [Dropped](#method.dropped)
+    /// and overflowed invalidities could not be tested and are only counted
+    /// as dropped in the second context, i.e. the first context contains only
+    /// invalidities that are known to satisfy the predicate.
+    pub fn partition<F>(self, mut predicate: F) -> (Self, Self)
+    where
+        F: FnMut(&V) -> bool,
+    {
+        let Context {
+            invalidities,
+            len,
+            warnings,
+            fail_fast,
+            limit,
+            paths,
+            dropped,
+        } = self;
+        let empty = || Self {
+            fail_fast,
+            limit,
+            paths,
+            ..Default::default()
+        };
+        let (mut left, mut right) = (empty(), empty());
+        // Invalidities that overflowed the accumulator could not be tested
+        let overflowed = len - invalidities.as_slice().len();
+        right.dropped = dropped + overflowed;
+        for item in invalidities.into_items() {
+            let context = if predicate(&item.1) {
+                &mut left
+            } else {
+                &mut right
+            };
+            context.record(1, once(item));
+        }
+        for item in warnings {
+            let context = if predicate(&item.1) {
+                &mut left
+            } else {
+                &mut right
+            };
+            context.merge_warnings(once(item));
+        }
+        (left, right)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<V, const N: usize> Context<V, DefaultAccumulator<V, N>>
+where
+    V: Invalidity,
+{
+    /// Convert all invalidities into a different type
+    ///
+    /// Both errors and warnings are mapped, preserving their paths.
+    /// The configuration and the number of [dropped](#method.dropped)
+    /// invalidities are retained.
+    pub fn map<F, U>(self, map: F) -> Context<U, DefaultAccumulator<U, N>>
+    where
+        F: Fn(V) -> U,
+        U: Invalidity,
+    {
+        self.map_with(|invalidities| invalidities.map(&map), &map)
+    }
+}
+
+impl<V, const N: usize> Context<V, FixedAccumulator<V, N>>
+where
+    V: Invalidity,
+{
+    /// Convert all invalidities into a different type
+    ///
+    /// Both errors and warnings are mapped, preserving their paths.
+    /// The configuration and the number of [dropped](#method.dropped)
+    /// and [overflowed](struct.FixedAccumulator.html#method.overflowed)
+    /// invalidities are retained.
+    pub fn map<F, U>(self, map: F) -> Context<U, FixedAccumulator<U, N>>
+    where
+        F: Fn(V) -> U,
+        U: Invalidity,
+    {
+        self.map_with(|invalidities| invalidities.map(&map), &map)
+    }
+}
+
+/// Collect invalidities as errors
+///
+/// All invalidities are recorded with an empty path.
+impl<V, A> FromIterator<V> for Context<V, A>
+where
+    V: Invalidity,
+    A: Accumulator<V>,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+    {
+        let mut context = Self::default();
+        context.extend(iter);
+        context
+    }
+}
+
+/// Record invalidities as errors
+///
+/// All invalidities are recorded with an empty path, respecting
+/// the mode and the limit of the context.
+impl<V, A> Extend<V> for Context<V, A>
+where
+    V: Invalidity,
+    A: Accumulator<V>,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = V>,
+    {
+        let iter = iter.into_iter();
+        let (count_hint, _) = iter.size_hint();
+        self.record(count_hint, iter.map(|invalidity| (Path::new(), invalidity)));
+    }
+}
+
+fn without_path<V>((_, invalidity): (Path, V)) -> V {
+    invalidity
+}
+
+impl<V, A> From<Context<V, A>> for Result<V, A>
+where
+    V: Invalidity,
+    A: Accumulator<V>,
+{
+    fn from(from: Context<V, A>) -> Self {
+        from.into_result()
+    }
+}
+
+/// Transform the validation context into an iterator
+/// that yields all the collected invalidities.
+impl<V, A> IntoIterator for Context<V, A>
+where
+    V: Invalidity,
+    A: Storage<V>,
+{
+    type Item = V;
+    // TODO: Replace with an opaque, existential type eventually (if ever possible):
+    // type IntoIter = impl Iterator<V>;
+    type IntoIter = Map<A::IntoItems, fn((Path, V)) -> V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.invalidities
+            .into_items()
+            .map(without_path as fn((Path, V)) -> V)
+    }
+}
+
+impl<V, A> Context<V, A>
+where
+    V: Invalidity,
+    A: Storage<V>,
+{
+    /// List all invalidities together with their paths, each
+    /// invalidity is formatted by `fmt_invalidity`
+    pub(crate) fn fmt_with(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        fmt_invalidity: fn(&V, &mut fmt::Formatter<'_>) -> fmt::Result,
+    ) -> fmt::Result {
+        for (i, (path, invalidity)) in self.iter_with_paths().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            if !path.is_empty() {
+                write!(f, "{}: ", path)?;
+            }
+            fmt_invalidity(invalidity, f)?;
+        }
+        if self.dropped > 0 {
+            if !self.invalidities.as_slice().is_empty() {
+                f.write_str("; ")?;
+            }
+            match self.dropped {
+                1 => f.write_str("1 more invalidity")?,
+                dropped => write!(f, "{} more invalidities", dropped)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// List all invalidities together with their paths
+///
+/// Invalidities are separated by semicolons. Each invalidity is
+/// prefixed by its path unless the path is empty.
+impl<V, A> fmt::Display for Context<V, A>
+where
+    V: Invalidity + fmt::Display,
+    A: Storage<V>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_with(f, fmt::Display::fmt)
+    }
+}
+
+/// A context with invalidities can be returned as an error
+///
+/// The operator `?` implicitly converts the context into a boxed error.
+#[cfg(feature = "std")]
+impl<V, A> std::error::Error for Context<V, A>
+where
+    V: Invalidity + fmt::Display,
+    A: Storage<V> + fmt::Debug,
+{
+}
+
+#[cfg(feature = "std")]
+impl<V> Context<V>
+where
+    V: Invalidity + fmt::Display + Send + Sync,
+{
+    /// Convert the context into a boxed error
+    ///
+    /// Useful for mapping the error of a validation result, i.e.
+    /// `validate().map_err(Context::into_boxed_error)`.
+    pub fn into_boxed_error(self) -> Box<dyn std::error::Error + Send + Sync> {
+        Box::new(self)
+    }
+}
+
+/// Serialize all invalidities as a sequence without their paths
+///
+/// Use [`with_paths`](struct.Context.html#method.with_paths) for
+/// serializing the invalidities together with their paths.
+#[cfg(feature = "serde")]
+impl<V, A> ::serde::Serialize for Context<V, A>
+where
+    V: Invalidity + ::serde::Serialize,
+    A: Storage<V>,
+{
+    fn serialize<S>(&self, serializer: S) -> CoreResult<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+/// Deserialize all invalidities from a sequence
+///
+/// The paths of the deserialized invalidities are empty.
+#[cfg(feature = "serde")]
+impl<'de, V, A> ::serde::Deserialize<'de> for Context<V, A>
+where
+    V: Invalidity + ::serde::Deserialize<'de>,
+    A: Storage<V>,
+{
+    fn deserialize<D>(deserializer: D) -> CoreResult<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        struct ContextVisitor<V, A>(core::marker::PhantomData<(V, A)>);
+
+        impl<'de, V, A> ::serde::de::Visitor<'de> for ContextVisitor<V, A>
+        where
+            V: Invalidity + ::serde::Deserialize<'de>,
+            A: Storage<V>,
+        {
+            type Value = Context<V, A>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a sequence of invalidities")
+            }
+
+            fn visit_seq<S>(self, mut seq: S) -> CoreResult<Self::Value, S::Error>
+            where
+                S: ::serde::de::SeqAccess<'de>,
+            {
+                let mut context = Context::default();
+                while let Some(invalidity) = seq.next_element::<V>()? {
+                    context = context.invalidate(invalidity);
+                }
+                Ok(context)
+            }
+        }
+
+        deserializer.deserialize_seq(ContextVisitor(core::marker::PhantomData))
+    }
+}
+
+/// A serializable view on all invalidities of a context together with their paths
+///
+/// Serialized as a sequence of structs with the fields `path` and `invalidity`.
+/// Paths are serialized as strings.
+///
+/// The view can only be serialized, contexts are deserialized from a sequence
+/// of invalidities without paths. The number of [dropped](struct.Context.html#method.dropped)
+/// invalidities is not serialized, neither with nor without paths.
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub struct WithPaths<'a, V, A = DefaultAccumulator<V>>(&'a Context<V, A>)
+where
+    V: Invalidity,
+    A: Accumulator<V>;
+
+#[cfg(feature = "serde")]
+impl<V, A> Context<V, A>
+where
+    V: Invalidity,
+    A: Storage<V>,
+{
+    /// Serialize all invalidities together with their paths
+    pub fn with_paths(&self) -> WithPaths<'_, V, A> {
+        WithPaths(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<V, A> ::serde::Serialize for WithPaths<'_, V, A>
+where
+    V: Invalidity + ::serde::Serialize,
+    A: Storage<V>,
+{
+    fn serialize<S>(&self, serializer: S) -> CoreResult<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        #[derive(::serde::Serialize)]
+        struct PathInvalidity<'a, V> {
+            path: &'a Path,
+            invalidity: &'a V,
+        }
+
+        serializer.collect_seq(
+            self.0
+                .iter_with_paths()
+                .map(|(path, invalidity)| PathInvalidity { path, invalidity }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test_fixtures::Leaf;
+
+    #[test]
+    fn valid_context() {
+        let context = Context::<()>::new();
+        assert!(context.is_empty());
+        assert!(context.is_valid());
+        assert!(context.into_result().is_ok());
+    }
+
+    #[test]
+    fn default_context() {
+        assert_eq!(Context::<()>::new(), Context::<()>::default());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn invalidate() {
+        let mut context = Context::<()>::new();
+        assert!(context.is_empty());
+        assert!(context.is_valid());
+        for _ in 0..=DEFAULT_INLINE_CAPACITY {
+            let invalidities_before = context.invalidities.len();
+            context = context.invalidate(());
+            assert!(!context.is_empty());
+            assert!(!context.is_valid());
+            let invalidities_after = context.invalidities.len();
+            assert_eq!(invalidities_after, invalidities_before + 1);
+        }
+        assert_eq!(DEFAULT_INLINE_CAPACITY + 1, context.invalidities.len());
+        assert!(context.into_result().is_err());
+    }
+
+    struct Node {
+        left: Leaf,
+        right: Leaf,
+    }
+
+    impl Validate for Node {
+        type Invalidity = ();
+
+        fn validate(&self) -> Result<Self::Invalidity> {
+            Context::new()
+                .validate_at("left", &self.left)
+                .validate_at("right", &self.right)
+                .into()
+        }
+    }
+
+    #[test]
+    fn validate_at() {
+        let context = Context::<()>::new()
+            .invalidate(())
+            .validate(&Leaf(false))
+            .validate_at(1, &Leaf(false))
+            .validate_at_with(
+                "node",
+                &Node {
+                    left: Leaf(false),
+                    right: Leaf(true),
+                },
+                |()| (),
+            );
+        let paths: Vec<_> = context
+            .into_iter_with_paths()
+            .map(|(path, ())| path)
+            .collect();
+        assert_eq!(
+            vec![
+                Path::new(),
+                Path::new(),
+                Path::from(PathSegment::Index(1)),
+                Path::from(PathSegment::Field("left")).prefixed("node".into()),
+            ],
+            paths
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[derive(Debug)]
+    struct Invalid(&'static str);
+
+    #[cfg(feature = "std")]
+    impl fmt::Display for Invalid {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "invalid {}", self.0)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn display() {
+        assert_eq!("", Context::<Invalid>::new().to_string());
+        let context = Context::<Invalid>::new()
+            .invalidate(Invalid("name"))
+            .validate_at_with(
+                "left",
+                &Node {
+                    left: Leaf(false),
+                    right: Leaf(false),
+                },
+                |()| Invalid("leaf"),
+            );
+        assert_eq!(
+            "invalid name; left.left: invalid leaf; left.right: invalid leaf",
+            context.to_string()
+        );
+        let context = Context::<Invalid>::with_limit(1)
+            .invalidate(Invalid("name"))
+            .invalidate(Invalid("id"))
+            .invalidate(Invalid("email"));
+        assert_eq!("invalid name; 2 more invalidities", context.to_string());
+        let context = Context::<Invalid>::with_limit(1)
+            .invalidate(Invalid("name"))
+            .invalidate(Invalid("id"));
+        assert_eq!("invalid name; 1 more invalidity", context.to_string());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn boxed_error() {
+        fn validate_node(node: &Node) -> core::result::Result<(), Box<dyn std::error::Error>> {
+            Context::<Invalid>::new()
+                .validate_at_with("node", node, |()| Invalid("leaf"))
+                .into_result()?;
+            Ok(())
+        }
+        let err = validate_node(&Node {
+            left: Leaf(true),
+            right: Leaf(false),
+        })
+        .unwrap_err();
+        assert_eq!("node.right: invalid leaf", err.to_string());
+        let err = Context::<Invalid>::new()
+            .invalidate(Invalid("name"))
+            .into_result()
+            .map_err(Context::into_boxed_error)
+            .unwrap_err();
+        assert_eq!("invalid name", err.to_string());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        let context = Context::<Option<u8>>::new()
+            .invalidate(Some(1))
+            .invalidate(None);
+        let json = serde_json::to_string(&context).unwrap();
+        assert_eq!("[1,null]", json);
+        assert_eq!(context, serde_json::from_str(&json).unwrap());
+        assert_eq!(
+            Context::<Option<u8>>::new(),
+            serde_json::from_str("[]").unwrap()
+        );
+        assert!(serde_json::from_str::<Context<Option<u8>>>("{}").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_with_paths() {
+        let context = Context::<u8>::new().invalidate(1).validate_at_with(
+            "node",
+            &Node {
+                left: Leaf(true),
+                right: Leaf(false),
+            },
+            |()| 2,
+        );
+        assert_eq!(
+            r#"[{"path":"","invalidity":1},{"path":"node.right","invalidity":2}]"#,
+            serde_json::to_string(&context.with_paths()).unwrap()
+        );
+    }
+
+    #[test]
+    fn fail_fast() {
+        let context = Context::<()>::fail_fast();
+        assert!(context.is_fail_fast());
+        assert!(!Context::<()>::new().is_fail_fast());
+        let context = context
+            .invalidate(())
+            .invalidate(())
+            .merge_result(Context::new().invalidate(()).invalidate(()).into());
+        assert_eq!(1, context.into_iter().count());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn fail_fast_skips_nested_validations() {
+        use crate::test_fixtures::Counted;
+        use core::cell::Cell;
+
+        let count = Cell::new(0);
+        let targets = [
+            Counted(&count, true),
+            Counted(&count, false),
+            Counted(&count, false),
+            Counted(&count, true),
+        ];
+        assert!(!targets.is_valid());
+        assert_eq!(2, count.get());
+        let context = Context::<()>::fail_fast()
+            .validate_at("targets", &targets)
+            .validate(&Counted(&count, false));
+        assert_eq!(4, count.get());
+        let paths: Vec<_> = context
+            .into_iter_with_paths()
+            .map(|(path, _)| path.to_string())
+            .collect();
+        assert_eq!(vec!["targets[1]"], paths);
+        count.set(0);
+        assert_eq!(2, targets.validate().unwrap_err().into_iter().count());
+        assert_eq!(4, count.get());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn with_limit() {
+        let context = Context::<u8>::with_limit(2);
+        assert_eq!(Some(2), context.limit());
+        assert_eq!(None, Context::<u8>::new().limit());
+        let context = context
+            .invalidate(1)
+            .validate_at_with(
+                "node",
+                &Node {
+                    left: Leaf(false),
+                    right: Leaf(false),
+                },
+                |()| 2,
+            )
+            .validate_with(&[Leaf(false), Leaf(false)], |()| 3);
+        assert_eq!(3, context.dropped());
+        assert!(!context.is_valid());
+        let paths: Vec<_> = context
+            .into_iter_with_paths()
+            .map(|(path, _)| path.to_string())
+            .collect();
+        assert_eq!(vec!["", "node.left"], paths);
+    }
+
+    #[test]
+    fn zero_limit() {
+        let context = Context::<()>::with_limit(0).invalidate(());
+        assert!(!context.is_valid());
+        assert_eq!(1, context.dropped());
+        assert!(context.into_result().is_err());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn warnings() {
+        struct Phone(&'static str);
+
+        impl Validate for Phone {
+            type Invalidity = &'static str;
+
+            fn validate(&self) -> Result<Self::Invalidity> {
+                Context::new()
+                    .invalidate_if(self.0.is_empty(), "empty")
+                    .warn_if(self.0.len() > 12, "unusual")
+                    .into()
+            }
+        }
+
+        let warnings = Phone("+49 123 456 789").validate().unwrap();
+        assert_eq!(1, warnings.len());
+        assert_eq!(
+            vec![(String::new(), "unusual")],
+            warnings
+                .into_iter()
+                .map(|(path, invalidity)| (path.to_string(), invalidity))
+                .collect::<Vec<_>>()
+        );
+
+        // Warnings of valid nested values reach the parent
+        let warnings = Context::new()
+            .validate_at("phone", &Phone("+49 123 456 789"))
+            .merge_result(Phone("+49 987 654 321").validate())
+            .into_result()
+            .unwrap();
+        assert_eq!(
+            vec![("phone".to_string(), "unusual"), (String::new(), "unusual")],
+            warnings
+                .iter()
+                .map(|(path, invalidity)| (path.to_string(), *invalidity))
+                .collect::<Vec<_>>()
+        );
+
+        let context = Context::new()
+            .warn("suspicious")
+            .validate_at("phone", &Phone("+49 123 456 789"))
+            .validate_at_with("fax", &Phone(""), |_| "no fax")
+            .into_result()
+            .unwrap_err();
+        let warnings: Vec<_> = context
+            .warnings()
+            .map(|(path, invalidity)| (path.to_string(), *invalidity))
+            .collect();
+        assert_eq!(
+            vec![
+                (String::new(), "suspicious"),
+                ("phone".to_string(), "unusual")
+            ],
+            warnings
+        );
+        assert_eq!(vec!["no fax"], context.into_iter().collect::<Vec<_>>());
+
+        let context = Context::<&str>::fail_fast().validate(&Phone("+49 123 456 789"));
+        assert!(context.is_valid());
+        assert!(!context.has_warnings());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn without_paths() {
+        assert!(Context::<()>::new().records_paths());
+        assert!(!Context::<(), usize>::default().records_paths());
+        let context = Context::new()
+            .without_paths()
+            .validate_at(
+                "node",
+                &Node {
+                    left: Leaf(false),
+                    right: Leaf(true),
+                },
+            )
+            .validate_at_with("leaf", &Leaf(false), |()| ());
+        assert!(context
+            .iter_with_paths()
+            .all(|(path, ())| path.segments().len() == 0));
+        assert_eq!(2, context.len());
+    }
+
+    #[test]
+    fn nested_overflow() {
+        struct Leafs(usize);
+
+        impl Validate for Leafs {
+            type Invalidity = ();
+
+            fn validate(&self) -> Result<Self::Invalidity> {
+                (0..self.0).map(|_| ()).collect::<Context<_>>().into()
+            }
+        }
+
+        let context = Context::<()>::new().validate(&Leafs(20));
+        assert_eq!(20, context.len() + context.dropped());
+        let context = Context::<()>::new().merge_result(Leafs(20).validate());
+        assert_eq!(20, context.len() + context.dropped());
+        // Without a heap allocator the default accumulator overflows
+        #[cfg(not(feature = "alloc"))]
+        assert_eq!(20 - DEFAULT_INLINE_CAPACITY, context.dropped());
+    }
+
+    #[test]
+    fn with_accumulator() {
+        let context = Context::<(), usize>::with_accumulator(0).invalidate(());
+        assert_eq!(1, context.len());
+        assert_eq!(1, context.into_accumulator());
+    }
+
+    #[test]
+    #[should_panic(expected = "accumulator is not empty")]
+    fn with_non_empty_accumulator() {
+        Context::<(), usize>::with_accumulator(1);
+    }
+
+    #[test]
+    fn counting_accumulator() {
+        let context = Context::<(), usize>::default().invalidate(()).validate(&[
+            Leaf(false),
+            Leaf(true),
+            Leaf(false),
+        ]);
+        assert!(!context.is_valid());
+        assert_eq!(3, *context.accumulator());
+        assert_eq!(3, context.into_accumulator());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn nested_accumulator() {
+        let context = Context::<(), FixedAccumulator<(), 2>>::default().validate_at(
+            "leafs",
+            &[Leaf(false), Leaf(true), Leaf(false), Leaf(false)],
+        );
+        assert_eq!(3, context.len());
+        let invalidities = context.into_accumulator();
+        assert_eq!(1, invalidities.overflowed());
+        assert_eq!(
+            vec!["leafs[0]", "leafs[2]"],
+            invalidities
+                .iter()
+                .map(|(path, ())| path.to_string())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn unit_accumulator() {
+        let context = Context::<(), ()>::default();
+        assert!(context.into_result().is_ok());
+        let context = Context::<(), _>::with_accumulator(()).validate_at("leaf", &Leaf(false));
+        assert!(context.into_result().is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn custom_accumulator() {
+        #[derive(Debug, Default)]
+        struct Paths(Vec<String>);
+
+        impl Mergeable for Paths {
+            type Item = String;
+
+            fn empty<H>(_: H) -> Self {
+                Default::default()
+            }
+
+            fn merge(mut self, mut other: Self) -> Self {
+                self.0.append(&mut other.0);
+                self
+            }
+
+            fn merge_iter<H, I>(mut self, _: H, iter: I) -> Self
+            where
+                I: Iterator<Item = Self::Item>,
+            {
+                self.0.extend(iter);
+                self
+            }
+        }
+
+        impl<V> Accumulator<V> for Paths {
+            fn item(path: Path, _: V) -> Self::Item {
+                path.to_string()
+            }
+        }
+
+        let paths = Context::<(), Paths>::default()
+            .validate_at(
+                "node",
+                &Node {
+                    left: Leaf(false),
+                    right: Leaf(false),
+                },
+            )
+            .into_result()
+            .unwrap_err()
+            .into_accumulator();
+        assert_eq!(vec!["node.left", "node.right"], paths.0);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn inline_capacity() {
+        use core::mem::size_of;
+
+        type Large = [u8; 64];
+        assert!(
+            size_of::<Context<Large, DefaultAccumulator<Large, 0>>>()
+                < size_of::<Context<Large, DefaultAccumulator<Large, 1>>>()
+        );
+        assert!(
+            size_of::<Context<Large, DefaultAccumulator<Large, 1>>>() < size_of::<Context<Large>>()
+        );
+        let context = Context::<u8, DefaultAccumulator<u8, 0>>::default()
+            .invalidate(1)
+            .validate_at_with("leaf", &Leaf(false), |()| 2);
+        let paths: Vec<_> = context
+            .into_iter_with_paths()
+            .map(|(path, invalidity)| (path.to_string(), invalidity))
+            .collect();
+        assert_eq!(vec![(String::new(), 1), ("leaf".to_string(), 2)], paths);
+    }
+
+    #[test]
+    fn fixed_accumulator() {
+        let context = Context::<u8, FixedAccumulator<u8, 2>>::default()
+            .invalidate(1)
+            .validate_with(&Leaf(false), |()| 2);
+        let context = context.merge(
+            Context::<u8, FixedAccumulator<u8, 2>>::default()
+                .invalidate(3)
+                .invalidate(4),
+        );
+        assert!(!context.is_valid());
+        let invalidities = context.into_accumulator();
+        assert!(invalidities.is_full());
+        assert_eq!(2, invalidities.len());
+        assert_eq!(2, invalidities.overflowed());
+        assert_eq!(
+            vec![1, 2],
+            invalidities.into_iter().map(|(_, v)| v).collect::<Vec<_>>()
+        );
+        let context = Context::<u8, FixedAccumulator<u8, 0>>::default().invalidate(1);
+        assert!(!context.is_valid());
+        assert_eq!(1, context.into_accumulator().overflowed());
+    }
+
+    #[test]
+    fn fixed_accumulator_storage() {
+        let context = Context::<u8, FixedAccumulator<u8, 3>>::default()
+            .invalidate(1)
+            .validate_at_with("leaf", &Leaf(false), |()| 2)
+            .invalidate(3)
+            .invalidate(4);
+        assert_eq!(4, context.len());
+        assert_eq!(vec![&1, &2, &3], context.iter().collect::<Vec<_>>());
+        let (even, odd) = context.clone().partition(|invalidity| invalidity % 2 == 0);
+        assert_eq!(vec![2], even.into_iter().collect::<Vec<_>>());
+        assert_eq!(1, odd.dropped());
+        assert_eq!(vec![1, 3], odd.into_iter().collect::<Vec<_>>());
+        let filtered = context.clone().filter(|_| false);
+        assert!(!filtered.is_valid());
+        assert_eq!(1, filtered.len());
+        let context = context.map(u16::from);
+        assert_eq!(4, context.len());
+        assert_eq!(
+            Some((&Path::from(PathSegment::Field("leaf")), &2)),
+            context.iter_with_paths().nth(1)
+        );
+        assert_eq!(1, context.into_accumulator().overflowed());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn in_place() {
+        let leafs = [Leaf(true), Leaf(false), Leaf(false)];
+        let mut context = Context::<u8>::new();
+        for (index, leaf) in leafs.iter().enumerate() {
+            if index > 0 {
+                context.nested_at(index, leaf, |()| 2);
+            } else {
+                context.check(leaf.0, 1).check(!leaf.0, 0);
+            }
+        }
+        context.push(3).nested(&Leaf(true), |()| 4);
+        let context = context.invalidate(5);
+        let paths: Vec<_> = context
+            .into_iter_with_paths()
+            .map(|(path, invalidity)| (path.to_string(), invalidity))
+            .collect();
+        assert_eq!(
+            vec![
+                (String::new(), 1),
+                ("[1]".to_string(), 2),
+                ("[2]".to_string(), 2),
+                (String::new(), 3),
+                (String::new(), 5),
+            ],
+            paths
+        );
+    }
+
+    #[test]
+    fn in_place_fail_fast() {
+        let mut context = Context::<u8>::fail_fast();
+        context.push(1).push(2).nested(&Leaf(false), |()| 3);
+        assert_eq!(vec![1], context.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn query() {
+        let context = Context::<u8>::new().invalidate(1).warn(2).validate_at_with(
+            "leaf",
+            &Leaf(false),
+            |()| 3,
+        );
+        assert_eq!(2, context.len());
+        assert_eq!(Some(&1), context.first());
+        assert!(context.contains(&3));
+        assert!(!context.contains(&2));
+        assert_eq!(vec![&1, &3], context.iter().collect::<Vec<_>>());
+        assert_eq!(None, Context::<u8>::new().first());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn map() {
+        let context = Context::<u8>::with_limit(2)
+            .invalidate(1)
+            .warn(2)
+            .validate_at_with("leaf", &Leaf(false), |()| 3)
+            .invalidate(4)
+            .map(|invalidity| invalidity.to_string());
+        assert_eq!(Some(2), context.limit());
+        assert_eq!(1, context.dropped());
+        assert_eq!(
+            vec![(String::new(), "2".to_string())],
+            context
+                .warnings()
+                .map(|(path, invalidity)| (path.to_string(), invalidity.clone()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                (String::new(), "1".to_string()),
+                ("leaf".to_string(), "3".to_string())
+            ],
+            context
+                .into_iter_with_paths()
+                .map(|(path, invalidity)| (path.to_string(), invalidity))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn filter_and_retain() {
+        let context: Context<u8> = (1..=4).collect();
+        let mut context = context
+            .warn(5)
+            .warn(6)
+            .filter(|invalidity| invalidity % 2 == 0);
+        assert_eq!(vec![2, 4], context.iter().copied().collect::<Vec<_>>());
+        assert_eq!(1, context.warnings().len());
+        context.retain(|invalidity| *invalidity > 4);
+        assert!(context.is_valid());
+        assert_eq!(0, context.len());
+        assert_eq!(
+            vec![&6],
+            context.warnings().map(|(_, w)| w).collect::<Vec<_>>()
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn sparse_paths() {
+        let mut context = Context::<u8>::new()
+            .invalidate(1)
+            .validate_at_with("leaf", &Leaf(false), |()| 2)
+            .invalidate(3)
+            .validate_at_with(
+                "node",
+                &Node {
+                    left: Leaf(false),
+                    right: Leaf(false),
+                },
+                |()| 4,
+            );
+        // Only non-empty paths are stored
+        assert_eq!(3, context.accumulator().paths.0.len());
+        context.retain(|invalidity| *invalidity != 2);
+        let expected = vec![
+            (String::new(), 1),
+            (String::new(), 3),
+            ("node.left".to_string(), 4),
+            ("node.right".to_string(), 4),
+        ];
+        assert_eq!(
+            expected,
+            context
+                .iter_with_paths()
+                .map(|(path, invalidity)| (path.to_string(), *invalidity))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            expected.into_iter().rev().collect::<Vec<_>>(),
+            context
+                .into_iter_with_paths()
+                .rev()
+                .map(|(path, invalidity)| (path.to_string(), invalidity))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn partition() {
+        let mut context = Context::<u8>::with_limit(3);
+        context.extend(1..=4);
+        let (even, odd) = context.warn(5).partition(|invalidity| invalidity % 2 == 0);
+        assert_eq!(vec![2], even.iter().copied().collect::<Vec<_>>());
+        assert!(!even.has_warnings());
+        assert_eq!(vec![1, 3], odd.iter().copied().collect::<Vec<_>>());
+        assert!(odd.has_warnings());
+        assert_eq!((0, 1), (even.dropped(), odd.dropped()));
+        assert_eq!(4, even.len() + even.dropped() + odd.len() + odd.dropped());
+        let (valid, invalid) = Context::<u8>::new()
+            .invalidate(1)
+            .partition(|invalidity| *invalidity > 1);
+        assert!(valid.is_valid());
+        assert!(!invalid.is_valid());
+    }
+
+    #[test]
+    fn collect_and_extend() {
+        let mut context: Context<u8, usize> = vec![1, 2].into_iter().collect();
+        context.extend(vec![3]);
+        assert_eq!(3, context.len());
+        assert_eq!(3, *context.accumulator());
+        let mut context = Context::<u8>::fail_fast();
+        context.extend(1..=3);
+        assert_eq!(vec![1], context.into_iter().collect::<Vec<_>>());
     }
 }
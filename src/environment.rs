@@ -0,0 +1,259 @@
+use super::*;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Validate a value that depends on an external environment
+///
+/// The environment provides everything that is required for the
+/// validation, but is not part of the validated value itself, e.g.
+/// configuration parameters, the current time, or reference data.
+///
+/// # Example
+/// ```
+/// # use semval::prelude::*;
+/// struct Currencies(Vec<&'static str>);
+///
+/// struct Price {
+///     currency: &'static str,
+/// }
+///
+/// #[derive(Debug)]
+/// enum PriceInvalidity {
+///     Currency,
+/// }
+///
+/// impl ValidateIn<Currencies> for Price {
+///     type Invalidity = PriceInvalidity;
+///
+///     fn validate_in(&self, env: &Currencies) -> ValidationResult<Self::Invalidity> {
+///         ValidationContext::new()
+///             .invalidate_if(
+///                 !env.0.contains(&self.currency),
+///                 PriceInvalidity::Currency,
+///             )
+///             .into()
+///     }
+/// }
+///
+/// let currencies = Currencies(vec!["EUR", "USD"]);
+/// assert!(Price { currency: "EUR" }.validate_in(&currencies).is_ok());
+/// assert!(vec![Price { currency: "EUR" }, Price { currency: "XYZ" }]
+///     .validate_in(&currencies)
+///     .is_err());
+/// ```
+pub trait ValidateIn<E>
+where
+    E: ?Sized,
+{
+    /// Invalidity objectives
+    type Invalidity: Invalidity;
+
+    /// Perform the validation within the given environment
+    fn validate_in(&self, env: &E) -> Result<Self::Invalidity>;
+
+    /// Perform the validation within the given environment and push
+    /// all invalidities into a sink
+    ///
+    /// The default implementation merges the result of [`validate_in`](#tymethod.validate_in)
+    /// into the sink. Implementations with nested validations should
+    /// override this method like [`Validate::validate_into`](../trait.Validate.html#method.validate_into).
+    fn validate_in_into(&self, env: &E, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        if sink.is_done() {
+            return;
+        }
+        match self.validate_in(env) {
+            Ok(warnings) => warnings.merge_into(sink),
+            Err(context) => context.merge_into(sink),
+        }
+    }
+}
+
+/// `ValidateIn` is implemented for any reference of a type
+/// that implements `ValidateIn`.
+impl<E, V> ValidateIn<E> for &V
+where
+    E: ?Sized,
+    V: ValidateIn<E> + ?Sized,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate_in(&self, env: &E) -> Result<Self::Invalidity> {
+        (**self).validate_in(env)
+    }
+
+    fn validate_in_into(&self, env: &E, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        (**self).validate_in_into(env, sink);
+    }
+}
+
+/// Validate `Some` or otherwise implicitly evaluate to `Ok`
+/// in case of `None`
+impl<E, V> ValidateIn<E> for Option<V>
+where
+    E: ?Sized,
+    V: ValidateIn<E>,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate_in(&self, env: &E) -> Result<Self::Invalidity> {
+        if let Some(ref some) = self {
+            some.validate_in(env)
+        } else {
+            Ok(Default::default())
+        }
+    }
+
+    fn validate_in_into(&self, env: &E, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        if let Some(ref some) = self {
+            some.validate_in_into(env, sink);
+        }
+    }
+}
+
+/// Validate all elements of a slice at their [indices](../path/enum.PathSegment.html#variant.Index) within the same environment
+impl<E, V> ValidateIn<E> for [V]
+where
+    E: ?Sized,
+    V: ValidateIn<E>,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate_in(&self, env: &E) -> Result<Self::Invalidity> {
+        let mut context = Context::new();
+        self.validate_in_into(env, &mut context);
+        context.into()
+    }
+
+    fn validate_in_into(&self, env: &E, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        for (index, elem) in self.iter().enumerate() {
+            if sink.is_done() {
+                break;
+            }
+            elem.validate_in_into(env, &mut PrefixSink::new(sink, index.into()));
+        }
+    }
+}
+
+/// Validate all elements of an array within the same environment
+impl<E, V, const N: usize> ValidateIn<E> for [V; N]
+where
+    E: ?Sized,
+    V: ValidateIn<E>,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate_in(&self, env: &E) -> Result<Self::Invalidity> {
+        self[..].validate_in(env)
+    }
+
+    fn validate_in_into(&self, env: &E, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        self[..].validate_in_into(env, sink);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<E, V> ValidateIn<E> for Vec<V>
+where
+    E: ?Sized,
+    V: ValidateIn<E>,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate_in(&self, env: &E) -> Result<Self::Invalidity> {
+        self.as_slice().validate_in(env)
+    }
+
+    fn validate_in_into(&self, env: &E, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        self.as_slice().validate_in_into(env, sink);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test_fixtures::Quantity;
+
+    struct MaxQuantity(usize);
+
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    struct QuantityExceeded;
+
+    impl ValidateIn<MaxQuantity> for Quantity {
+        type Invalidity = QuantityExceeded;
+
+        fn validate_in(&self, env: &MaxQuantity) -> Result<Self::Invalidity> {
+            Context::new()
+                .invalidate_if(self.0 > env.0, QuantityExceeded)
+                .into()
+        }
+    }
+
+    #[test]
+    fn validate_option() {
+        let max = MaxQuantity(2);
+        assert!(None::<Quantity>.validate_in(&max).is_ok());
+        assert!(Some(Quantity(2)).validate_in(&max).is_ok());
+        assert!(Some(Quantity(3)).validate_in(&max).is_err());
+        assert!(Some(Quantity(3)).validate_in(&MaxQuantity(3)).is_ok());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn validate_slices() {
+        let max = MaxQuantity(2);
+        let quantities = [Quantity(1), Quantity(3), Quantity(2), Quantity(4)];
+        let paths: Vec<_> = quantities
+            .validate_in(&max)
+            .unwrap_err()
+            .into_iter_with_paths()
+            .map(|(path, _)| path.to_string())
+            .collect();
+        assert_eq!(vec!["[1]", "[3]"], paths);
+        assert!(quantities[..1].validate_in(&max).is_ok());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn validate_in_context() {
+        let max = MaxQuantity(2);
+        let context = Context::<QuantityExceeded>::new()
+            .validate_in(&Quantity(3), &max)
+            .validate_at_in("quantities", &vec![Quantity(1), Quantity(5)], &max);
+        let paths: Vec<_> = context
+            .into_iter_with_paths()
+            .map(|(path, _)| path.to_string())
+            .collect();
+        assert_eq!(vec!["", "quantities[1]"], paths);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn validate_in_fail_fast() {
+        struct CountedMax(core::cell::Cell<usize>);
+
+        impl ValidateIn<CountedMax> for Quantity {
+            type Invalidity = QuantityExceeded;
+
+            fn validate_in(&self, env: &CountedMax) -> Result<Self::Invalidity> {
+                env.0.set(env.0.get() + 1);
+                Context::new().invalidate(QuantityExceeded).into()
+            }
+        }
+
+        let env = CountedMax(core::cell::Cell::new(0));
+        let quantities: Vec<_> = (0..100).map(Quantity).collect();
+        let context = Context::<QuantityExceeded>::fail_fast().validate_in(&quantities, &env);
+        assert_eq!(1, context.len());
+        assert_eq!(1, env.0.get());
+        env.0.set(0);
+        let context = Context::<QuantityExceeded>::with_limit(2).validate_at_in(
+            "quantities",
+            &quantities,
+            &env,
+        );
+        assert_eq!((2, 98), (context.len(), context.dropped()));
+        assert_eq!(100, env.0.get());
+    }
+}
@@ -1,8 +1,8 @@
 #![deny(missing_docs)]
 #![deny(missing_debug_implementations)]
-#![deny(broken_intra_doc_links)]
+#![deny(rustdoc::broken_intra_doc_links)]
 #![cfg_attr(test, deny(warnings))]
-#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 
 //! # semval
 //!
@@ -10,37 +10,115 @@
 //!
 //! Please refer to the bundled `reservation.rs` example to get an idea of how it works.
 //!
-//! Without any macro magic, at least not now.
+//! Without any macro magic, unless you opt in: The `derive` feature provides
+//! a derive macro for `Validate` that generates the validation of nested fields.
+//!
+//! The crate is `no_std` if the default feature `std` is disabled. Enable
+//! the `alloc` feature on targets with a heap allocator to validate boxed
+//! values and collections like `Vec` or `BTreeMap` without requiring `std`.
+//! Without `alloc` the crate doesn't require a heap allocator at all, see
+//! [`FixedAccumulator`](context/struct.FixedAccumulator.html).
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+/// Asynchronous validation
+#[cfg(feature = "async")]
+pub mod asynchronous;
+
+/// Validation of collections
+pub mod collections;
 
 /// Invalidity context
 pub mod context;
 
+/// Validation within an external environment
+pub mod environment;
+
+/// Parallel validation
+#[cfg(feature = "rayon")]
+pub mod parallel;
+
+/// Paths of invalidities
+pub mod path;
+
+/// Deserialization of validated values
+#[cfg(feature = "serde")]
+pub mod serde;
+
+/// Recording invalidities directly in sinks
+pub mod sink;
+
+/// Validation of tuples
+pub mod tuple;
+
+/// Validated values
+pub mod validated;
+
 /// The crate's prelude
 ///
 /// A proposed set of imports to ease usage of this crate.
 pub mod prelude {
     pub use super::{
-        context::Context as ValidationContext, IntoValidated, Invalidity, IsValid,
-        Result as ValidationResult, Validate, ValidatedFrom, ValidatedResult,
+        context::Context as ValidationContext,
+        environment::ValidateIn,
+        sink::InvaliditySink,
+        validated::{ModifyGuard, Validated},
+        IntoValidated, Invalidity, IsValid, Result as ValidationResult, Validate, ValidatedFrom,
+        ValidatedResult,
     };
+
+    #[cfg(feature = "async")]
+    pub use super::asynchronous::{AsyncContext, AsyncValidate};
 }
 
+#[cfg(feature = "alloc")]
 mod smallvec;
 mod util;
 
-use self::{context::Context, util::*};
+#[cfg(test)]
+mod test_fixtures;
+
+/// Derive macro for implementing `Validate`
+///
+/// See [semval-derive](https://docs.rs/semval-derive) for the supported attributes.
+#[cfg(feature = "derive")]
+pub use semval_derive::Validate;
+
+/// Traits for accumulating items, e.g. within a custom
+/// [`Accumulator`](context/trait.Accumulator.html)
+pub use self::util::{IsEmpty, Mergeable, MergeableSized};
+
+use self::{
+    context::{Context, DefaultAccumulator, Warnings},
+    sink::{InvaliditySink, PrefixSink},
+};
 
-use core::{any::Any, fmt::Debug, result::Result as CoreResult};
+use core::{any::Any, fmt::Debug, ops::Deref, pin::Pin, result::Result as CoreResult};
+
+#[cfg(feature = "alloc")]
+use alloc::{
+    borrow::{Cow, ToOwned},
+    boxed::Box,
+    rc::Rc,
+    vec::Vec,
+};
+
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+use alloc::sync::Arc;
 
 /// Result of a validation
 ///
-/// The result is `Ok` and empty if the validation succeeded. It is
-/// a validation context wrapped into `Err` that carries one or more
-/// invalidities.
+/// The result is `Ok` if the validation succeeded. It is a validation
+/// context wrapped into `Err` that carries one or more invalidities.
 ///
 /// In contrast to common results the actual payload is carried by
-/// the error variant while a successful result is just the unit type.
-pub type Result<V> = UnitResult<Context<V>>;
+/// the error variant while a successful result only carries the
+/// [`Warnings`](context/struct.Warnings.html) of the validation, if any.
+///
+/// The context type depends on the accumulator, see
+/// [`Accumulator`](context/trait.Accumulator.html).
+pub type Result<V, A = DefaultAccumulator<V>> = CoreResult<Warnings<V>, Context<V, A>>;
 
 /// Invalidities that cause validation failures
 ///
@@ -71,6 +149,44 @@ pub trait Validate {
 
     /// Perform the validation
     fn validate(&self) -> Result<Self::Invalidity>;
+
+    /// Perform the validation within an existing context
+    ///
+    /// All invalidities are recorded in the given context by
+    /// [`validate_into`](#method.validate_into), which should be overridden
+    /// instead of this method.
+    fn validate_within(&self, mut context: Context<Self::Invalidity>) -> Context<Self::Invalidity> {
+        self.validate_into(&mut context);
+        context
+    }
+
+    /// Perform the validation and push all invalidities into a sink
+    ///
+    /// The default implementation merges the result of [`validate`](#tymethod.validate)
+    /// into the sink. All nested validations of a [`Context`](context/struct.Context.html)
+    /// are performed by this method.
+    ///
+    /// Implementations with nested validations should override this method
+    /// and pass the sink on to the nested validations, wrapped into a
+    /// [`PrefixSink`](sink/struct.PrefixSink.html) and a
+    /// [`MapSink`](sink/struct.MapSink.html). All invalidities are then
+    /// pushed directly into the root sink, e.g. a context, without
+    /// collecting them in an intermediate context for each nested
+    /// validation. This also enables to skip the remaining validations
+    /// of a [fail-fast](context/struct.Context.html#method.fail_fast) context
+    /// as soon as the first invalidity has been found.
+    ///
+    /// Implementations that override this method may implement
+    /// [`validate`](#tymethod.validate) by `self.validate_within(Context::new()).into()`.
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        if sink.is_done() {
+            return;
+        }
+        match self.validate() {
+            Ok(warnings) => warnings.merge_into(sink),
+            Err(context) => context.merge_into(sink),
+        }
+    }
 }
 
 /// A utility trait for boolean validity checks.
@@ -82,23 +198,115 @@ pub trait IsValid {
 
 impl<T> IsValid for T
 where
-    T: Validate,
+    T: Validate + ?Sized,
 {
     fn is_valid(&self) -> bool {
-        self.validate().is_ok()
+        self.validate_within(Context::fail_fast()).is_valid()
     }
 }
 
 /// `Validate` is implemented for any reference of a type
 /// that implements `Validate`.
-impl<'a, V> Validate for &'a V
+impl<V> Validate for &V
 where
-    V: Validate,
+    V: Validate + ?Sized,
 {
     type Invalidity = V::Invalidity;
 
     fn validate(&self) -> Result<Self::Invalidity> {
-        (*self).validate()
+        (**self).validate()
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        (**self).validate_into(sink);
+    }
+}
+
+/// `Validate` is implemented for any boxed type that implements `Validate`.
+#[cfg(feature = "alloc")]
+impl<V> Validate for Box<V>
+where
+    V: Validate + ?Sized,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        (**self).validate()
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        (**self).validate_into(sink);
+    }
+}
+
+/// `Validate` is implemented for any shared type that implements `Validate`.
+#[cfg(feature = "alloc")]
+impl<V> Validate for Rc<V>
+where
+    V: Validate + ?Sized,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        (**self).validate()
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        (**self).validate_into(sink);
+    }
+}
+
+/// `Validate` is implemented for any shared type that implements `Validate`.
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl<V> Validate for Arc<V>
+where
+    V: Validate + ?Sized,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        (**self).validate()
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        (**self).validate_into(sink);
+    }
+}
+
+/// Validate either the borrowed or the owned value
+///
+/// Both variants are validated through the borrowed type.
+#[cfg(feature = "alloc")]
+impl<V> Validate for Cow<'_, V>
+where
+    V: Validate + ToOwned + ?Sized,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        (**self).validate()
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        (**self).validate_into(sink);
+    }
+}
+
+/// `Validate` is implemented for any pinned pointer to a type
+/// that implements `Validate`.
+impl<P> Validate for Pin<P>
+where
+    P: Deref,
+    P::Target: Validate,
+{
+    type Invalidity = <P::Target as Validate>::Invalidity;
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        (**self).validate()
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        (**self).validate_into(sink);
     }
 }
 
@@ -117,12 +325,18 @@ where
         if let Some(ref some) = self {
             some.validate()
         } else {
-            Ok(())
+            Ok(Default::default())
+        }
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        if let Some(ref some) = self {
+            some.validate_into(sink);
         }
     }
 }
 
-/// Validate all elements of a slice
+/// Validate all elements of a slice at their [indices](path/enum.PathSegment.html#variant.Index)
 impl<V> Validate for [V]
 where
     V: Validate,
@@ -130,13 +344,36 @@ where
     type Invalidity = V::Invalidity;
 
     fn validate(&self) -> Result<Self::Invalidity> {
-        self.iter()
-            .fold(Context::new(), |ctx, elem| ctx.validate(elem))
-            .into()
+        self.validate_within(Context::new()).into()
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        for (index, elem) in self.iter().enumerate() {
+            if sink.is_done() {
+                break;
+            }
+            elem.validate_into(&mut PrefixSink::new(sink, index.into()));
+        }
+    }
+}
+
+/// Validate all elements of an array at their [indices](path/enum.PathSegment.html#variant.Index)
+impl<V, const N: usize> Validate for [V; N]
+where
+    V: Validate,
+{
+    type Invalidity = V::Invalidity;
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        self[..].validate()
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        self[..].validate_into(sink);
     }
 }
 
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 impl<V> Validate for Vec<V>
 where
     V: Validate,
@@ -146,6 +383,10 @@ where
     fn validate(&self) -> Result<Self::Invalidity> {
         self.as_slice().validate()
     }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        self.as_slice().validate_into(sink);
+    }
 }
 
 /// Result of a value-to-value conversion with post-validation of the output value
@@ -258,6 +499,8 @@ where
 }
 
 #[cfg(test)]
+// The tests borrow explicitly to cover the implementations for references
+#[allow(clippy::needless_borrow)]
 mod tests {
     use super::*;
 
@@ -362,6 +605,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validate_slices_with_paths() {
+        let paths: Vec<_> = [Dummy::invalid(), Dummy::valid(), Dummy::invalid()]
+            .validate()
+            .unwrap_err()
+            .into_iter_with_paths()
+            .map(|(path, ())| path)
+            .collect();
+        assert_eq!(
+            vec![
+                path::Path::from(path::PathSegment::Index(0)),
+                path::Path::from(path::PathSegment::Index(2)),
+            ],
+            paths
+        );
+    }
+
     #[test]
     #[cfg(feature = "std")]
     fn validate_borrowed_vec() {
@@ -401,6 +661,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validate_arrays() {
+        assert!([Dummy::valid(), Dummy::valid()].validate().is_ok());
+        assert!(([] as [Dummy; 0]).validate().is_ok());
+        assert_eq!(
+            2,
+            [Dummy::invalid(), Dummy::valid(), Dummy::invalid()]
+                .validate()
+                .unwrap_err()
+                .into_iter()
+                .count()
+        );
+    }
+
+    #[test]
+    fn validate_pinned() {
+        let valid = Dummy::valid();
+        let invalid = Dummy::invalid();
+        assert!(Pin::new(&valid).validate().is_ok());
+        assert!(Pin::new(&invalid).validate().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn validate_smart_pointers() {
+        assert!(Box::new(Dummy::valid()).validate().is_ok());
+        assert!(Box::new(Dummy::invalid()).validate().is_err());
+        assert!(Rc::new(Dummy::valid()).validate().is_ok());
+        assert!(Rc::new(Dummy::invalid()).validate().is_err());
+        assert!(Arc::new(Dummy::valid()).validate().is_ok());
+        assert!(Arc::new(Dummy::invalid()).validate().is_err());
+        assert!(Box::pin(Dummy::valid()).validate().is_ok());
+        assert!(Box::pin(Dummy::invalid()).validate().is_err());
+        let boxed_slice: Box<[Dummy]> = vec![Dummy::valid(), Dummy::invalid()].into_boxed_slice();
+        assert!(boxed_slice.validate().is_err());
+        assert!(!boxed_slice.is_valid());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn validate_cow() {
+        use crate::test_fixtures::Leaf;
+
+        assert!(Cow::Borrowed(&Leaf(false)).validate().is_err());
+        assert!(Cow::<Leaf>::Owned(Leaf(false)).validate().is_err());
+        assert!(Cow::<Leaf>::Owned(Leaf(true)).validate().is_ok());
+        let slice = [Leaf(true), Leaf(false)];
+        assert!(Cow::Borrowed(&slice[..]).validate().is_err());
+        assert!(Cow::Borrowed(&slice[..1]).validate().is_ok());
+    }
+
     #[test]
     fn validated_from() {
         assert!(AlwaysValid::validated_from(AlwaysValid).is_ok());
@@ -0,0 +1,128 @@
+use super::*;
+
+use rayon::prelude::*;
+
+/// Parallel validation of large collections
+///
+/// All elements are validated in parallel using [rayon](https://docs.rs/rayon).
+/// The resulting invalidities are the same and in the same order as
+/// when validating all elements sequentially.
+///
+/// # Example
+/// ```
+/// # use semval::prelude::*;
+/// use semval::parallel::ParValidate;
+///
+/// #[derive(Debug)]
+/// struct Quantity(usize);
+///
+/// impl Validate for Quantity {
+///     type Invalidity = ();
+///
+///     fn validate(&self) -> ValidationResult<Self::Invalidity> {
+///         ValidationContext::new().invalidate_if(self.0 < 1, ()).into()
+///     }
+/// }
+///
+/// let quantities: Vec<_> = (0..1000).map(|i| Quantity(i % 100)).collect();
+/// let context = quantities.par_validate().unwrap_err();
+/// assert_eq!(10, context.into_iter().count());
+/// ```
+pub trait ParValidate {
+    /// Invalidity objectives
+    type Invalidity: Invalidity;
+
+    /// Perform the validation in parallel
+    fn par_validate(&self) -> Result<Self::Invalidity>;
+}
+
+/// Validate all elements of a slice at their [indices](../path/enum.PathSegment.html#variant.Index) in parallel
+impl<V> ParValidate for [V]
+where
+    V: Validate + Sync,
+    V::Invalidity: Send,
+{
+    type Invalidity = V::Invalidity;
+
+    fn par_validate(&self) -> Result<Self::Invalidity> {
+        self.par_iter()
+            .enumerate()
+            .map(|(index, elem)| Context::new().validate_at(index, elem))
+            .reduce(Context::new, Mergeable::merge)
+            .into()
+    }
+}
+
+impl<V, const N: usize> ParValidate for [V; N]
+where
+    V: Validate + Sync,
+    V::Invalidity: Send,
+{
+    type Invalidity = V::Invalidity;
+
+    fn par_validate(&self) -> Result<Self::Invalidity> {
+        self[..].par_validate()
+    }
+}
+
+impl<V> ParValidate for Vec<V>
+where
+    V: Validate + Sync,
+    V::Invalidity: Send,
+{
+    type Invalidity = V::Invalidity;
+
+    fn par_validate(&self) -> Result<Self::Invalidity> {
+        self.as_slice().par_validate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Element(usize);
+
+    impl Validate for Element {
+        type Invalidity = usize;
+
+        fn validate(&self) -> Result<Self::Invalidity> {
+            Context::new()
+                .invalidate_if(self.0 % 7 == 0, self.0)
+                .warn_if(self.0 % 11 == 0, self.0)
+                .into()
+        }
+    }
+
+    #[test]
+    fn same_as_sequential() {
+        let elements: Vec<_> = (0..10_000).map(Element).collect();
+        let sequential = elements.validate().unwrap_err();
+        let parallel = elements.par_validate().unwrap_err();
+        assert_eq!(sequential, parallel);
+        // Every 11th element is suspicious, even if it is also invalid
+        assert_eq!(910, parallel.warnings().count());
+        assert_eq!(
+            Some("[11]".to_string()),
+            parallel.warnings().nth(1).map(|(path, _)| path.to_string())
+        );
+        let paths: Vec<_> = parallel
+            .into_iter_with_paths()
+            .take(3)
+            .map(|(path, invalidity)| (path.to_string(), invalidity))
+            .collect();
+        assert_eq!(
+            vec![
+                ("[0]".to_string(), 0),
+                ("[7]".to_string(), 7),
+                ("[14]".to_string(), 14)
+            ],
+            paths
+        );
+        assert!([Element(1), Element(2)].par_validate().is_ok());
+        let warnings = [Element(1), Element(11), Element(22)]
+            .par_validate()
+            .unwrap();
+        assert_eq!(2, warnings.len());
+    }
+}
@@ -0,0 +1,186 @@
+#[cfg(feature = "alloc")]
+use crate::smallvec::SmallVec;
+
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+type Segments = SmallVec<[PathSegment; 0]>;
+
+// Paths are only recorded on targets with a heap allocator
+#[cfg(not(feature = "alloc"))]
+type Segments = [PathSegment; 0];
+
+/// A single step within a path
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum PathSegment {
+    /// A named member of a struct
+    Field(&'static str),
+
+    /// The position of an element within a sequence or tuple
+    ///
+    /// The validations of slices, arrays, tuples, and sequential collections
+    /// record the index of each element in the paths of its invalidities.
+    Index(usize),
+}
+
+impl From<&'static str> for PathSegment {
+    fn from(from: &'static str) -> Self {
+        PathSegment::Field(from)
+    }
+}
+
+impl From<usize> for PathSegment {
+    fn from(from: usize) -> Self {
+        PathSegment::Index(from)
+    }
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSegment::Field(name) => f.write_str(name),
+            PathSegment::Index(index) => write!(f, "[{}]", index),
+        }
+    }
+}
+
+/// The location of an invalidity within the validated target
+///
+/// Paths are recorded while passing the invalidities of nested validations
+/// on to the parent, i.e. from the inside out. Invalidities that are recorded
+/// directly within a context have an empty path.
+///
+/// Empty paths don't allocate any memory. Contexts that don't need paths
+/// could skip recording them, see [`Context::without_paths`](../context/struct.Context.html#method.without_paths).
+/// Without feature `alloc` paths are never recorded and always empty.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Path {
+    // Innermost segment first for prefixing paths in constant time
+    segments: Segments,
+}
+
+impl Path {
+    /// Create an empty path
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            #[cfg(feature = "alloc")]
+            segments: SmallVec::new_const(),
+            #[cfg(not(feature = "alloc"))]
+            segments: [],
+        }
+    }
+
+    /// Check if the path is empty, i.e. refers to the validated target itself
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// The segments of this path, starting with the outermost segment
+    #[inline]
+    pub fn segments(&self) -> impl ExactSizeIterator<Item = &PathSegment> + DoubleEndedIterator {
+        self.segments.iter().rev()
+    }
+
+    /// Prepend an outer segment
+    #[cfg(feature = "alloc")]
+    pub(crate) fn prefixed(mut self, segment: PathSegment) -> Self {
+        self.segments.push(segment);
+        self
+    }
+
+    /// Prepend an outer segment
+    #[cfg(not(feature = "alloc"))]
+    pub(crate) fn prefixed(self, _: PathSegment) -> Self {
+        self
+    }
+}
+
+impl From<PathSegment> for Path {
+    fn from(from: PathSegment) -> Self {
+        Self::new().prefixed(from)
+    }
+}
+
+/// Format paths like `customer.contact_data.email` or `items[3].quantity`
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments().enumerate() {
+            if i > 0 {
+                if let PathSegment::Field(_) = segment {
+                    f.write_str(".")?;
+                }
+            }
+            fmt::Display::fmt(segment, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Serialize paths as strings, e.g. `items[3].quantity`
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for Path {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_path() {
+        assert!(Path::new().is_empty());
+        assert_eq!(0, Path::new().segments().len());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn prefixed_path() {
+        let path = Path::from(PathSegment::Field("email"))
+            .prefixed("contact_data".into())
+            .prefixed("customer".into());
+        assert!(path.segments().eq(&[
+            PathSegment::Field("customer"),
+            PathSegment::Field("contact_data"),
+            PathSegment::Field("email"),
+        ]));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn display_path() {
+        assert_eq!("", Path::new().to_string());
+        assert_eq!(
+            "customer.contact_data.email",
+            Path::from(PathSegment::Field("email"))
+                .prefixed("contact_data".into())
+                .prefixed("customer".into())
+                .to_string()
+        );
+        assert_eq!(
+            "items[3].quantity",
+            Path::from(PathSegment::Field("quantity"))
+                .prefixed(3.into())
+                .prefixed("items".into())
+                .to_string()
+        );
+        assert_eq!(
+            "[3][0]",
+            Path::from(PathSegment::Index(0))
+                .prefixed(3.into())
+                .to_string()
+        );
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn discard_segments() {
+        assert!(Path::from(PathSegment::Field("email")).is_empty());
+    }
+}
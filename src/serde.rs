@@ -0,0 +1,190 @@
+use super::*;
+
+use crate::validated::Validated;
+
+use ::serde::{de, Deserialize, Deserializer};
+use core::fmt;
+
+/// Failure of [`deserialize_and_validate`](fn.deserialize_and_validate.html)
+///
+/// Distinguishes errors of the deserializer, e.g. malformed input, from
+/// values that have been deserialized successfully but are invalid.
+#[derive(Debug)]
+pub enum Error<E, V>
+where
+    V: Invalidity,
+{
+    /// The input could not be deserialized
+    Deserialize(E),
+
+    /// The deserialized value is invalid
+    Invalid(Context<V>),
+}
+
+impl<E, V> fmt::Display for Error<E, V>
+where
+    E: fmt::Display,
+    V: Invalidity + fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Deserialize(err) => write!(f, "deserialization failed: {}", err),
+            Error::Invalid(context) => write!(f, "validation failed: {}", context),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E, V> std::error::Error for Error<E, V>
+where
+    E: std::error::Error + 'static,
+    V: Invalidity + fmt::Display,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Deserialize(err) => Some(err),
+            Error::Invalid(context) => Some(context),
+        }
+    }
+}
+
+/// Deserialize a value and validate it afterwards
+///
+/// # Example
+/// ```
+/// # use semval::prelude::*;
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Quantity(usize);
+///
+/// impl Validate for Quantity {
+///     type Invalidity = ();
+///
+///     fn validate(&self) -> ValidationResult<Self::Invalidity> {
+///         ValidationContext::new().invalidate_if(self.0 < 1, ()).into()
+///     }
+/// }
+///
+/// let deserialize = |json| {
+///     semval::serde::deserialize_and_validate::<_, Quantity>(
+///         &mut serde_json::Deserializer::from_str(json),
+///     )
+/// };
+/// assert_eq!(1, deserialize("1").unwrap().0);
+/// assert!(matches!(deserialize("0"), Err(semval::serde::Error::Invalid(_))));
+/// assert!(matches!(deserialize("-1"), Err(semval::serde::Error::Deserialize(_))));
+/// ```
+pub fn deserialize_and_validate<'de, D, T>(
+    deserializer: D,
+) -> CoreResult<Validated<T>, Error<D::Error, T::Invalidity>>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Validate,
+{
+    let value = T::deserialize(deserializer).map_err(Error::Deserialize)?;
+    Validated::try_new(value).map_err(|(_, context)| Error::Invalid(context))
+}
+
+fn deserialize_into_validated<'de, D, T>(deserializer: D) -> CoreResult<Validated<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Validate,
+{
+    deserialize_and_validate(deserializer).map_err(|err| match err {
+        Error::Deserialize(err) => err,
+        Error::Invalid(context) => de::Error::custom(Rejected(&context)),
+    })
+}
+
+/// The message of a custom deserialization error for an invalid value
+struct Rejected<'a, V>(&'a Context<V>)
+where
+    V: Invalidity;
+
+impl<V> fmt::Display for Rejected<'_, V>
+where
+    V: Invalidity,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid value: ")?;
+        self.0.fmt_with(f, fmt::Debug::fmt)
+    }
+}
+
+/// Deserialize and validate a value, compatible with `#[serde(deserialize_with = "...")]`
+///
+/// Invalid values are rejected by a custom deserialization error that
+/// contains the paths and the debug representations of all invalidities.
+pub fn deserialize_validated<'de, D, T>(deserializer: D) -> CoreResult<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Validate,
+{
+    deserialize_into_validated(deserializer).map(Validated::into_inner)
+}
+
+/// Only valid values are accepted, invalid values are rejected like
+/// by [`deserialize_validated`](serde/fn.deserialize_validated.html)
+impl<'de, T> Deserialize<'de> for Validated<T>
+where
+    T: Deserialize<'de> + Validate,
+{
+    fn deserialize<D>(deserializer: D) -> CoreResult<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_into_validated(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test_fixtures::{MinQuantity, Quantity};
+
+    #[derive(Debug, Deserialize)]
+    struct Reservation {
+        #[serde(deserialize_with = "deserialize_validated")]
+        quantity: Quantity,
+    }
+
+    #[test]
+    fn deserialize_validated_value() {
+        let quantity: Validated<Quantity> = serde_json::from_str("2").unwrap();
+        assert_eq!(2, quantity.0);
+        assert!(serde_json::from_str::<Validated<Quantity>>("0").is_err());
+        assert!(serde_json::from_str::<Validated<Quantity>>("\"2\"").is_err());
+    }
+
+    #[test]
+    fn deserialize_validated_field() {
+        let reservation: Reservation = serde_json::from_str(r#"{"quantity":1}"#).unwrap();
+        assert_eq!(1, reservation.quantity.0);
+        let err = serde_json::from_str::<Reservation>(r#"{"quantity":0}"#).unwrap_err();
+        assert!(err.to_string().starts_with("invalid value: MinQuantity"));
+    }
+
+    #[test]
+    fn reject_with_paths() {
+        let err = serde_json::from_str::<Validated<Vec<Quantity>>>("[1,0,0]").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("invalid value: [1]: MinQuantity; [2]: MinQuantity"));
+    }
+
+    #[test]
+    #[allow(clippy::result_large_err)]
+    fn distinguish_errors() {
+        let deserialize = |json| {
+            deserialize_and_validate::<_, Quantity>(&mut serde_json::Deserializer::from_str(json))
+        };
+        assert!(deserialize("1").is_ok());
+        match deserialize("0") {
+            Err(Error::Invalid(context)) => {
+                assert_eq!(vec![MinQuantity], context.into_iter().collect::<Vec<_>>());
+            }
+            res => panic!("unexpected result: {:?}", res),
+        }
+        assert!(matches!(deserialize("x"), Err(Error::Deserialize(_))));
+    }
+}
@@ -0,0 +1,313 @@
+use crate::path::{Path, PathSegment};
+
+use core::fmt;
+
+/// A receiver of invalidities that are recorded while validating
+///
+/// Invalidities are pushed directly into a sink by
+/// [`Validate::validate_into`](../trait.Validate.html#method.validate_into)
+/// instead of collecting them in an intermediate context for each nested
+/// validation. Every [`Context`](../context/struct.Context.html) is a sink
+/// and serves as the root of the validation.
+///
+/// Nested validations are connected to their parent sink by adapters that
+/// prefix the paths ([`PrefixSink`](struct.PrefixSink.html)) and map the
+/// invalidities ([`MapSink`](struct.MapSink.html)).
+///
+/// # Example
+/// ```
+/// # use semval::prelude::*;
+/// use semval::sink::{InvaliditySink, MapSink, PrefixSink};
+///
+/// #[derive(Debug)]
+/// struct Quantity(usize);
+///
+/// impl Validate for Quantity {
+///     type Invalidity = ();
+///
+///     fn validate(&self) -> ValidationResult<Self::Invalidity> {
+///         ValidationContext::new().invalidate_if(self.0 < 1, ()).into()
+///     }
+/// }
+///
+/// #[derive(Debug)]
+/// enum OrderInvalidity {
+///     Quantity,
+/// }
+///
+/// struct Order {
+///     quantity: Quantity,
+/// }
+///
+/// impl Validate for Order {
+///     type Invalidity = OrderInvalidity;
+///
+///     fn validate(&self) -> ValidationResult<Self::Invalidity> {
+///         ValidationContext::new()
+///             .validate_at_with("quantity", &self.quantity, |()| OrderInvalidity::Quantity)
+///             .into()
+///     }
+///
+///     fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+///         self.quantity.validate_into(&mut MapSink::new(
+///             &mut PrefixSink::new(sink, "quantity".into()),
+///             |()| OrderInvalidity::Quantity,
+///         ));
+///     }
+/// }
+///
+/// let mut context = ValidationContext::new();
+/// Order { quantity: Quantity(0) }.validate_into(&mut context);
+/// let (path, _) = context.into_iter_with_paths().next().unwrap();
+/// # #[cfg(feature = "alloc")]
+/// assert_eq!("quantity", path.to_string());
+/// ```
+pub trait InvaliditySink<V> {
+    /// Record an invalidity as an error
+    fn add_invalidity(&mut self, path: Path, invalidity: V);
+
+    /// Record an invalidity as a warning
+    fn add_warning(&mut self, path: Path, invalidity: V);
+
+    /// Count invalidities that have been recorded as errors,
+    /// but could not be stored
+    fn add_dropped(&mut self, count: usize);
+
+    /// Check if no more invalidities need to be collected
+    ///
+    /// Validations should skip all remaining checks as soon
+    /// as the sink is done, e.g. in fail-fast mode.
+    fn is_done(&self) -> bool;
+
+    /// Check if the sink records the paths of invalidities
+    ///
+    /// Paths are not built for sinks that discard them.
+    fn records_paths(&self) -> bool {
+        true
+    }
+}
+
+/// A sink that maps all invalidities before passing them on
+/// to the parent sink
+pub struct MapSink<'a, V, F> {
+    sink: &'a mut dyn InvaliditySink<V>,
+    map: F,
+}
+
+impl<'a, V, F> MapSink<'a, V, F> {
+    /// Wrap the parent sink
+    pub fn new(sink: &'a mut dyn InvaliditySink<V>, map: F) -> Self {
+        Self { sink, map }
+    }
+}
+
+impl<V, F> fmt::Debug for MapSink<'_, V, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MapSink").finish_non_exhaustive()
+    }
+}
+
+impl<U, V, F> InvaliditySink<U> for MapSink<'_, V, F>
+where
+    F: Fn(U) -> V,
+{
+    fn add_invalidity(&mut self, path: Path, invalidity: U) {
+        self.sink.add_invalidity(path, (self.map)(invalidity));
+    }
+
+    fn add_warning(&mut self, path: Path, invalidity: U) {
+        self.sink.add_warning(path, (self.map)(invalidity));
+    }
+
+    fn add_dropped(&mut self, count: usize) {
+        self.sink.add_dropped(count);
+    }
+
+    fn is_done(&self) -> bool {
+        self.sink.is_done()
+    }
+
+    fn records_paths(&self) -> bool {
+        self.sink.records_paths()
+    }
+}
+
+/// A sink that prefixes the paths of all invalidities with
+/// a segment before passing them on to the parent sink
+///
+/// The segment is typically the name of a field or the index of
+/// an element, i.e. the role of the nested target.
+pub struct PrefixSink<'a, V> {
+    sink: &'a mut dyn InvaliditySink<V>,
+    segment: PathSegment,
+}
+
+impl<'a, V> PrefixSink<'a, V> {
+    /// Wrap the parent sink
+    pub fn new(sink: &'a mut dyn InvaliditySink<V>, segment: PathSegment) -> Self {
+        Self { sink, segment }
+    }
+
+    fn prefixed(&self, path: Path) -> Path {
+        if self.sink.records_paths() {
+            path.prefixed(self.segment)
+        } else {
+            path
+        }
+    }
+}
+
+impl<V> fmt::Debug for PrefixSink<'_, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrefixSink")
+            .field("segment", &self.segment)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<V> InvaliditySink<V> for PrefixSink<'_, V> {
+    fn add_invalidity(&mut self, path: Path, invalidity: V) {
+        let path = self.prefixed(path);
+        self.sink.add_invalidity(path, invalidity);
+    }
+
+    fn add_warning(&mut self, path: Path, invalidity: V) {
+        let path = self.prefixed(path);
+        self.sink.add_warning(path, invalidity);
+    }
+
+    fn add_dropped(&mut self, count: usize) {
+        self.sink.add_dropped(count);
+    }
+
+    fn is_done(&self) -> bool {
+        self.sink.is_done()
+    }
+
+    fn records_paths(&self) -> bool {
+        self.sink.records_paths()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{context::Context, test_fixtures::Leaf, Result, Validate};
+
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    enum NodeInvalidity {
+        Leaf,
+        Balance,
+    }
+
+    struct Node {
+        leafs: [Leaf; 3],
+    }
+
+    impl Node {
+        fn is_balanced(&self) -> bool {
+            self.leafs.iter().filter(|leaf| leaf.0).count() != 1
+        }
+    }
+
+    impl Validate for Node {
+        type Invalidity = NodeInvalidity;
+
+        fn validate(&self) -> Result<Self::Invalidity> {
+            Context::new()
+                .validate_at_with("leafs", &self.leafs, |()| NodeInvalidity::Leaf)
+                .warn_if(!self.is_balanced(), NodeInvalidity::Balance)
+                .into()
+        }
+
+        fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+            self.leafs.validate_into(&mut MapSink::new(
+                &mut PrefixSink::new(sink, "leafs".into()),
+                |()| NodeInvalidity::Leaf,
+            ));
+            if !self.is_balanced() {
+                sink.add_warning(Path::new(), NodeInvalidity::Balance);
+            }
+        }
+    }
+
+    fn paths<V>(context: Context<V>) -> Vec<(String, V)>
+    where
+        V: crate::Invalidity,
+    {
+        context
+            .into_iter_with_paths()
+            .map(|(path, invalidity)| (path.to_string(), invalidity))
+            .collect()
+    }
+
+    #[test]
+    fn same_as_validate() {
+        let nodes = vec![
+            Node {
+                leafs: [Leaf(true), Leaf(false), Leaf(true)],
+            },
+            Node {
+                leafs: [Leaf(false), Leaf(true), Leaf(false)],
+            },
+        ];
+        let expected = nodes.validate().unwrap_err();
+        let mut context = Context::new();
+        nodes.validate_into(&mut context);
+        assert_eq!(expected.warnings().len(), context.warnings().len());
+        assert_eq!(paths(expected), paths(context));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn default_implementation() {
+        let mut context = Context::new();
+        [Leaf(true), Leaf(false)].validate_into(&mut context);
+        Leaf(false).validate_into(&mut context);
+        assert_eq!(
+            vec![("[1]".to_string(), ()), (String::new(), ())],
+            paths(context)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn fail_fast() {
+        let node = Node {
+            leafs: [Leaf(false), Leaf(false), Leaf(false)],
+        };
+        let mut context = Context::fail_fast();
+        node.validate_into(&mut context);
+        assert!(!context.has_warnings());
+        assert_eq!(
+            vec![("leafs[0]".to_string(), NodeInvalidity::Leaf)],
+            paths(context)
+        );
+    }
+
+    #[test]
+    fn with_limit() {
+        let leafs = [Leaf(false); 4];
+        let mut context = Context::with_limit(3);
+        leafs.validate_into(&mut context);
+        assert_eq!(1, context.dropped());
+        assert_eq!(3, context.into_iter().count());
+    }
+
+    #[test]
+    fn without_paths() {
+        let node = Node {
+            leafs: [Leaf(false), Leaf(true), Leaf(false)],
+        };
+        let mut context = Context::new().without_paths();
+        node.validate_into(&mut context);
+        assert_eq!(
+            vec![
+                (String::new(), NodeInvalidity::Leaf),
+                (String::new(), NodeInvalidity::Leaf)
+            ],
+            paths(context)
+        );
+    }
+}
@@ -2,7 +2,7 @@
 use crate::util::*;
 
 /// Re-exports
-pub(crate) use smallvec::{Array, IntoIter, SmallVec};
+pub(crate) use smallvec::{IntoIter, SmallVec};
 
 impl<A> IsEmpty for smallvec::SmallVec<A>
 where
@@ -31,17 +31,18 @@ where
         }
     }
 
-    fn merge(self, other: Self) -> Self {
-        // Reuse the instance with greater capacity for accumulation (this)
-        // and consume (= drain & drop) the other one (that).
-        let (that, mut this) = if self.capacity() < other.capacity() {
-            (self, other)
+    fn merge(mut self, mut other: Self) -> Self {
+        // Reuse the instance with greater capacity for accumulation
+        // and consume (= drain & drop) the other one while preserving
+        // the order of all items.
+        if self.capacity() < other.capacity() {
+            other.insert_many(0, self);
+            other
         } else {
-            (other, self)
-        };
-        this.reserve(that.len());
-        this.insert_many(this.len(), that.into_iter());
-        this
+            self.reserve(other.len());
+            self.insert_many(self.len(), other);
+            self
+        }
     }
 
     fn merge_iter<H, I>(mut self, count_hint: H, iter: I) -> Self
@@ -54,3 +55,16 @@ where
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_preserves_order() {
+        let small: SmallVec<[usize; 2]> = [1, 2].iter().copied().collect();
+        let large: SmallVec<[usize; 2]> = SmallVec::with_capacity(16).merge_iter(2, 3..=4);
+        assert_eq!(&[1, 2, 3, 4], small.clone().merge(large.clone()).as_slice());
+        assert_eq!(&[3, 4, 1, 2], large.merge(small).as_slice());
+    }
+}
@@ -0,0 +1,48 @@
+//! Validation targets that are shared by the tests of all modules
+
+// Not every fixture is used with every combination of features
+#![allow(dead_code)]
+
+use crate::{context::Context, Result, Validate};
+
+use core::cell::Cell;
+
+/// A leaf of a tree that is either valid or invalid
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub(crate) struct Leaf(pub(crate) bool);
+
+impl Validate for Leaf {
+    type Invalidity = ();
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        Context::new().invalidate_if(!self.0, ()).into()
+    }
+}
+
+/// A quantity that must be positive
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize))]
+pub(crate) struct Quantity(pub(crate) usize);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct MinQuantity;
+
+impl Validate for Quantity {
+    type Invalidity = MinQuantity;
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        Context::new().invalidate_if(self.0 < 1, MinQuantity).into()
+    }
+}
+
+/// A leaf that counts how often it has been validated
+pub(crate) struct Counted<'a>(pub(crate) &'a Cell<usize>, pub(crate) bool);
+
+impl Validate for Counted<'_> {
+    type Invalidity = ();
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        self.0.set(self.0.get() + 1);
+        Context::new().invalidate_if(!self.1, ()).into()
+    }
+}
@@ -0,0 +1,237 @@
+use crate::{
+    context::Context,
+    path::PathSegment,
+    sink::{InvaliditySink, MapSink, PrefixSink},
+    Result, Validate,
+};
+
+macro_rules! impl_validate_for_tuple {
+    ($invalidity:ident, $len:literal, $($index:tt: $variant:ident: $ty:ident),+) => {
+        #[doc = concat!("Invalidities of a tuple with ", stringify!($len), " element(s)")]
+        ///
+        /// Each variant wraps the invalidity of the element at the corresponding position.
+        #[allow(non_camel_case_types)]
+        #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+        #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+        pub enum $invalidity<$($ty),+> {
+            $(
+                #[doc = concat!("Invalidity of element ", stringify!($index))]
+                $variant($ty),
+            )+
+        }
+
+        /// Validate all elements of a tuple
+        ///
+        /// The position of each element is recorded in the paths of its invalidities.
+        impl<$($ty),+> Validate for ($($ty,)+)
+        where
+            $($ty: Validate,)+
+        {
+            type Invalidity = $invalidity<$($ty::Invalidity),+>;
+
+            fn validate(&self) -> Result<Self::Invalidity> {
+                self.validate_within(Context::new()).into()
+            }
+
+            fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+                $(
+                    if sink.is_done() {
+                        return;
+                    }
+                    self.$index.validate_into(&mut MapSink::new(
+                        &mut PrefixSink::new(sink, PathSegment::Index($index)),
+                        $invalidity::$variant,
+                    ));
+                )+
+            }
+        }
+    };
+}
+
+impl_validate_for_tuple!(Tuple1Invalidity, 1, 0: _0: A);
+impl_validate_for_tuple!(Tuple2Invalidity, 2, 0: _0: A, 1: _1: B);
+impl_validate_for_tuple!(Tuple3Invalidity, 3, 0: _0: A, 1: _1: B, 2: _2: C);
+impl_validate_for_tuple!(Tuple4Invalidity, 4, 0: _0: A, 1: _1: B, 2: _2: C, 3: _3: D);
+impl_validate_for_tuple!(
+    Tuple5Invalidity,
+    5,
+    0: _0: A,
+    1: _1: B,
+    2: _2: C,
+    3: _3: D,
+    4: _4: E
+);
+impl_validate_for_tuple!(
+    Tuple6Invalidity,
+    6,
+    0: _0: A,
+    1: _1: B,
+    2: _2: C,
+    3: _3: D,
+    4: _4: E,
+    5: _5: F
+);
+impl_validate_for_tuple!(
+    Tuple7Invalidity,
+    7,
+    0: _0: A,
+    1: _1: B,
+    2: _2: C,
+    3: _3: D,
+    4: _4: E,
+    5: _5: F,
+    6: _6: G
+);
+impl_validate_for_tuple!(
+    Tuple8Invalidity,
+    8,
+    0: _0: A,
+    1: _1: B,
+    2: _2: C,
+    3: _3: D,
+    4: _4: E,
+    5: _5: F,
+    6: _6: G,
+    7: _7: H
+);
+impl_validate_for_tuple!(
+    Tuple9Invalidity,
+    9,
+    0: _0: A,
+    1: _1: B,
+    2: _2: C,
+    3: _3: D,
+    4: _4: E,
+    5: _5: F,
+    6: _6: G,
+    7: _7: H,
+    8: _8: I
+);
+impl_validate_for_tuple!(
+    Tuple10Invalidity,
+    10,
+    0: _0: A,
+    1: _1: B,
+    2: _2: C,
+    3: _3: D,
+    4: _4: E,
+    5: _5: F,
+    6: _6: G,
+    7: _7: H,
+    8: _8: I,
+    9: _9: J
+);
+impl_validate_for_tuple!(
+    Tuple11Invalidity,
+    11,
+    0: _0: A,
+    1: _1: B,
+    2: _2: C,
+    3: _3: D,
+    4: _4: E,
+    5: _5: F,
+    6: _6: G,
+    7: _7: H,
+    8: _8: I,
+    9: _9: J,
+    10: _10: K
+);
+impl_validate_for_tuple!(
+    Tuple12Invalidity,
+    12,
+    0: _0: A,
+    1: _1: B,
+    2: _2: C,
+    3: _3: D,
+    4: _4: E,
+    5: _5: F,
+    6: _6: G,
+    7: _7: H,
+    8: _8: I,
+    9: _9: J,
+    10: _10: K,
+    11: _11: L
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        path::{Path, PathSegment},
+        test_fixtures::{MinQuantity, Quantity},
+    };
+
+    struct Name(&'static str);
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct NameEmpty;
+
+    impl Validate for Name {
+        type Invalidity = NameEmpty;
+
+        fn validate(&self) -> Result<Self::Invalidity> {
+            Context::new()
+                .invalidate_if(self.0.is_empty(), NameEmpty)
+                .into()
+        }
+    }
+
+    #[test]
+    fn validate_pair() {
+        assert!((Quantity(1), Name("Mr X")).validate().is_ok());
+        let invalidities: Vec<_> = (Quantity(0), Name(""))
+            .validate()
+            .unwrap_err()
+            .into_iter_with_paths()
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    Path::from(PathSegment::Index(0)),
+                    Tuple2Invalidity::_0(MinQuantity)
+                ),
+                (
+                    Path::from(PathSegment::Index(1)),
+                    Tuple2Invalidity::_1(NameEmpty)
+                ),
+            ],
+            invalidities
+        );
+    }
+
+    #[test]
+    fn validate_pair_fail_fast() {
+        let context = Context::fail_fast().validate(&(Quantity(0), Name("")));
+        assert_eq!(
+            vec![Tuple2Invalidity::_0(MinQuantity)],
+            context.into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn validate_single() {
+        assert!((Quantity(1),).validate().is_ok());
+        assert!((Quantity(0),).validate().is_err());
+    }
+
+    #[test]
+    fn validate_max_arity() {
+        let tuple = (
+            Quantity(1),
+            Quantity(1),
+            Quantity(1),
+            Quantity(1),
+            Quantity(1),
+            Quantity(1),
+            Quantity(1),
+            Quantity(1),
+            Quantity(1),
+            Quantity(1),
+            Quantity(1),
+            Name(""),
+        );
+        let invalidities: Vec<_> = tuple.validate().unwrap_err().into_iter().collect();
+        assert_eq!(vec![Tuple12Invalidity::_11(NameEmpty)], invalidities);
+    }
+}
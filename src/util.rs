@@ -1,11 +1,13 @@
-/// Traits and utilities for internal usage
+//! Traits and utilities for accumulating items
 
 ///////////////////////////////////////////////////////////////////////////////
-/// IsEmpty
+// IsEmpty
 ///////////////////////////////////////////////////////////////////////////////
 
 // TODO: Reuse from https://github.com/Stebalien/tool-rs?
-pub(crate) trait IsEmpty {
+/// Check if a collection or an accumulator contains any items
+pub trait IsEmpty {
+    /// Returns `true` if there are no items
     fn is_empty(&self) -> bool;
 }
 
@@ -24,11 +26,12 @@ impl IsEmpty for usize {
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-/// Mergeable
+// Mergeable
 ///////////////////////////////////////////////////////////////////////////////
 
 /// A monoid for collecting or accumulating items
-pub(crate) trait Mergeable {
+pub trait Mergeable {
+    /// The type of the accumulated items
     type Item;
 
     /// Create an empty instance
@@ -56,7 +59,10 @@ pub(crate) trait Mergeable {
         I: Iterator<Item = Self::Item>;
 }
 
-pub(crate) trait MergeableSized: Mergeable + Sized {
+/// Extension of [`Mergeable`](trait.Mergeable.html) for sized types
+pub trait MergeableSized: Mergeable + Sized {
+    /// Consuming combine operation that merges this instance with
+    /// all items of an iterator with a known length
     fn merge_exact_size_iter<I>(self, iter: I) -> Self
     where
         I: ExactSizeIterator<Item = Self::Item>,
@@ -97,7 +103,7 @@ impl Mergeable for usize {
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-/// UnitResult
+// UnitResult
 ///////////////////////////////////////////////////////////////////////////////
 
 /// A result with only an error and the unit type `()` on success
@@ -148,7 +154,7 @@ mod tests {
     fn unit_mergeable() {
         assert_eq!((), <() as Mergeable>::empty(5));
         assert_eq!((), ().merge(()));
-        assert_eq!((), ().merge_iter(3, core::iter::repeat(()).take(3)));
+        assert_eq!((), ().merge_iter(3, (0..3).map(|_| ())));
     }
 
     #[test]
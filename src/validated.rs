@@ -0,0 +1,233 @@
+use super::*;
+
+use core::{
+    borrow::Borrow,
+    ops::{Deref, DerefMut},
+};
+
+/// A value that has been validated successfully
+///
+/// Values of this type can only be obtained by validating them, i.e. by
+/// [`try_new`](#method.try_new) or through [ValidatedFrom](../trait.ValidatedFrom.html)
+/// by [`validated_from`](#method.validated_from). Functions that demand a
+/// `Validated<T>` as a parameter could not be called with values that have
+/// not been validated.
+///
+/// The wrapped value is accessible by immutable references. Modifications
+/// require to validate the value again, either by [`modify`](#method.modify)
+/// or by a [`ModifyGuard`](struct.ModifyGuard.html).
+///
+/// # Example
+/// ```
+/// # use semval::prelude::*;
+/// #[derive(Debug)]
+/// struct Quantity(usize);
+///
+/// impl Validate for Quantity {
+///     type Invalidity = ();
+///
+///     fn validate(&self) -> ValidationResult<Self::Invalidity> {
+///         ValidationContext::new().invalidate_if(self.0 < 1, ()).into()
+///     }
+/// }
+///
+/// fn reserve(quantity: &Validated<Quantity>) -> usize {
+///     quantity.0
+/// }
+///
+/// let quantity = Validated::try_new(Quantity(3)).unwrap();
+/// assert_eq!(3, reserve(&quantity));
+///
+/// let (quantity, _context) = Validated::try_new(Quantity(0)).unwrap_err();
+/// assert_eq!(0, quantity.0);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Validated<T>(T);
+
+impl<T> Validated<T>
+where
+    T: Validate,
+{
+    /// Validate the value
+    ///
+    /// On validation errors the value is returned together with all
+    /// invalidities.
+    pub fn try_new(value: T) -> CoreResult<Self, (T, Context<T::Invalidity>)> {
+        if let Err(context) = value.validate() {
+            Err((value, context))
+        } else {
+            Ok(Self(value))
+        }
+    }
+
+    /// Convert the input value into the wrapped value and validate it
+    ///
+    /// See also: [ValidatedFrom](../trait.ValidatedFrom.html)
+    pub fn validated_from<F>(from: F) -> CoreResult<Self, (T, Context<T::Invalidity>)>
+    where
+        T: ValidatedFrom<F>,
+    {
+        T::validated_from(from).map(Self)
+    }
+
+    /// Modify the wrapped value and validate it again
+    ///
+    /// On validation errors the modified value is returned together
+    /// with all invalidities.
+    pub fn modify<F>(self, modify: F) -> CoreResult<Self, (T, Context<T::Invalidity>)>
+    where
+        F: FnOnce(&mut T),
+    {
+        let mut guard = self.modify_guard();
+        modify(&mut guard);
+        guard.revalidate()
+    }
+
+    /// Obtain mutable access to the wrapped value
+    ///
+    /// The returned guard needs to be validated again to recover
+    /// the wrapper.
+    pub fn modify_guard(self) -> ModifyGuard<T> {
+        ModifyGuard(self.0)
+    }
+}
+
+impl<T> Validated<T> {
+    /// Unwrap the validated value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Validated<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> AsRef<T> for Validated<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> Borrow<T> for Validated<T> {
+    fn borrow(&self) -> &T {
+        &self.0
+    }
+}
+
+/// Validate the wrapped value again
+///
+/// Validation should be idempotent and is then expected to succeed.
+impl<T> Validate for Validated<T>
+where
+    T: Validate,
+{
+    type Invalidity = T::Invalidity;
+
+    fn validate(&self) -> Result<Self::Invalidity> {
+        self.0.validate()
+    }
+
+    fn validate_into(&self, sink: &mut dyn InvaliditySink<Self::Invalidity>) {
+        self.0.validate_into(sink);
+    }
+}
+
+/// Mutable access to a validated value
+///
+/// The guard is obtained from [`Validated::modify_guard`](struct.Validated.html#method.modify_guard)
+/// and dereferences mutably into the wrapped value. It must be consumed
+/// by [`revalidate`](#method.revalidate) to recover the
+/// [`Validated`](struct.Validated.html) wrapper after all modifications
+/// have been applied.
+#[derive(Debug)]
+#[must_use = "the modified value must be validated again"]
+pub struct ModifyGuard<T>(T);
+
+impl<T> ModifyGuard<T>
+where
+    T: Validate,
+{
+    /// Finish the modifications by validating the value again
+    ///
+    /// On validation errors the modified value is returned together
+    /// with all invalidities.
+    pub fn revalidate(self) -> CoreResult<Validated<T>, (T, Context<T::Invalidity>)> {
+        Validated::try_new(self.0)
+    }
+}
+
+impl<T> Deref for ModifyGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for ModifyGuard<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test_fixtures::Quantity;
+
+    struct NewQuantity(usize);
+
+    impl ValidatedFrom<NewQuantity> for Quantity {
+        fn validated_from(from: NewQuantity) -> ValidatedResult<Self> {
+            Self(from.0).into_validated()
+        }
+    }
+
+    #[test]
+    fn try_new() {
+        let validated = Validated::try_new(Quantity(1)).unwrap();
+        assert_eq!(&Quantity(1), &*validated);
+        assert!(validated.is_valid());
+        assert_eq!(Quantity(1), validated.into_inner());
+        let (invalid, context) = Validated::try_new(Quantity(0)).unwrap_err();
+        assert_eq!(Quantity(0), invalid);
+        assert!(!context.is_valid());
+    }
+
+    #[test]
+    fn validated_from() {
+        let validated = Validated::<Quantity>::validated_from(NewQuantity(2)).unwrap();
+        assert_eq!(&Quantity(2), validated.as_ref());
+        let (invalid, _) = Validated::<Quantity>::validated_from(NewQuantity(0)).unwrap_err();
+        assert_eq!(Quantity(0), invalid);
+    }
+
+    #[test]
+    fn modify() {
+        let validated = Validated::try_new(Quantity(1)).unwrap();
+        let validated = validated.modify(|quantity| quantity.0 += 1).unwrap();
+        assert_eq!(&Quantity(2), &*validated);
+        let (invalid, context) = validated.modify(|quantity| quantity.0 = 0).unwrap_err();
+        assert_eq!(Quantity(0), invalid);
+        assert!(!context.is_valid());
+    }
+
+    #[test]
+    fn modify_guard() {
+        let validated = Validated::try_new(Quantity(1)).unwrap();
+        let mut guard = validated.modify_guard();
+        *guard = Quantity(0);
+        (*guard).0 = 3;
+        let validated = guard.revalidate().unwrap();
+        assert_eq!(&Quantity(3), &*validated);
+        let mut guard = validated.modify_guard();
+        *guard = Quantity(0);
+        assert!(guard.revalidate().is_err());
+    }
+}